@@ -4,10 +4,12 @@
 //! manage file paths, file names, and other file-based
 //! operations.  I've used them in several different projects
 //! which is why I've kept them together in a separate crate.
-//! Their greatest weakness is poor error handling, so keep that
-//! in mind if you choose to use them.  By the way, I need help getting
-//! those weaknesses corrected, so if you feel like taking that on,
-//! please check
+//! Their greatest weakness used to be poor error handling.  Most
+//! functions now report their failures through
+//! `file_errors::LibFileError` rather than panicking or printing
+//! to the terminal, but keep that history in mind if you choose
+//! to use them.  By the way, I still need help, so if you feel like
+//! taking something on, please check
 //! out the issues tab in this crate's repository.
 //!
 //!
@@ -24,589 +26,3286 @@ pub mod file_fltk {
     // region todo's
     //todo: Passing usedir as an &String is clumsy.  Find a better way.
 
-    //todo:  When running this program with the first call to FLTK.rs's
-    //          file `dialog`, the dialog window opens on top of the primary
-    //          window just fine.  However, after that any subsequent dialog
-    //          windows open behind the primary window.  I need them to open
-    //          on top.  You need to fix this in every function.  Here's what the AI said:
-    //          ## Option 3: Use FLTK's Modal Windows (Best Long-term Solution)
-    //          The proper fix is to modify your `lib_file` crate's dialog functions.
-    //          If you can share or modify those functions, you should add parent window
-    //           support.   Here's an example of what the fix would look like:
-    //          ```rust
-    // use fltk::window::Window;
-    // pub fn file_fullpath_with_parent(path: &str, parent: Option<&Window>) -> String {
-    //     let mut dialog = fltk::dialog::FileDialog::new(fltk::dialog::FileDialogType::BrowseFile);
-    //     dialog.set_directory(path);
-    //     // Set parent if provided
-    //     if let Some(win) = parent {
-    //         dialog.set_modal(true);
-    //         // This ensures the dialog is associated with the parent window
-    //     }
-    //     dialog.show();
-    //     dialog.filename().to_string_lossy().to_string()
-    // }
-    //          ```
-
-    //todo: The native Linux file dialog browser does not allow the user to add
-    //      a title to the save-file dialog window.  That feature has been
-    //      removed from the function.
-    //      You may want to use FLTK's file dialog browser instead.
-    //      Is it worth it?  Experiment.
+    //note:  Subsequent native dialogs used to open behind the primary window.
+    //          Each dialog function now has a `_parent` twin that takes the
+    //          window the dialog belongs to and opens the dialog on top of it,
+    //          modal to it.  Only `DialogBackend::Fltk` can do that, so it is
+    //          used for those dialogs unless another backend was chosen.
+    //          See `DialogBuilder::parent()`.
+
+    //note:  The native Linux file dialog browser does not allow the user to add
+    //          a title to the save-file dialog window.  Use
+    //          `DialogBuilder::backend(DialogBackend::Fltk)`, or set it for every
+    //          dialog with `fltk_set_default_backend()`, to get FLTK's own
+    //          browser, which always shows the title.
     //endregion
 
-    use fltk::dialog;
-    use std::path::Path;
+    use fltk::{app, dialog, enums::{Event, Shortcut}, menu::{MenuBar, MenuFlag}, prelude::*, window::Window};
+    use std::{cell::RefCell, collections::VecDeque, env, fmt, path::{Path, PathBuf}, rc::Rc};
+    use lib_utils::{input_utilities::input_string_prompt, utilities::util_activity_menu};
     use crate::dir_mngmnt::*;
+    use crate::file_errors::LibFileError;
+    use crate::file_mngmnt::{file_check_extension, file_get_dir_list, file_glob_match, file_next_free_path,
+                             RecentFiles};
+
+    /// The kind of dialog a `DialogBuilder` opens.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum DialogMode {
+        /// Choose one existing file.
+        Open,
+        /// Choose a location and name for saving a file.
+        Save,
+        /// Choose a directory.
+        Dir,
+        /// Choose one or more existing files.
+        Multi,
+    }
+
+    /// What a `DialogBuilder` hands back for each chosen path.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ReturnShape {
+        /// The full path, including the file name.
+        FullPath,
+        /// Only the file name, with no path in front of it.
+        NameOnly,
+    }
+
+    /// What a validation callback (see `DialogBuilder::validate()`) decides
+    /// about a chosen path.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Validation {
+        /// The path will do.
+        Accept,
+        /// The path won't do, for the reason given.  The reason is shown to
+        /// the user before the dialog opens again.
+        Reject(String),
+    }
+
+    /// Holds a validation callback so `DialogBuilder` can stay `Clone` and `Debug`.
+    #[derive(Clone)]
+    struct Validator(Rc<dyn Fn(&str) -> Validation>);
+
+    impl fmt::Debug for Validator {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "Validator(..)")
+        }
+    }
+
+    /// Which file chooser a `DialogBuilder` shows.
+    #[derive(Clone, Default)]
+    pub enum DialogBackend {
+        /// The platform's own chooser (GTK, Windows, macOS).  This is the
+        /// default, except for dialogs given a parent window.
+        #[default]
+        Native,
+        /// FLTK's `dialog::FileChooser`.  It honours titles, previews and
+        /// filters the same way on every desktop, but doesn't look native.
+        Fltk,
+        /// Numbered menus and typed answers in the terminal.  Used in place of
+        /// `Native` and `Fltk` when `fltk_display_available()` is `false`.
+        Terminal,
+        /// Any other `DialogRunner`, such as a `ScriptedRunner` in a test.
+        Custom(Rc<RefCell<dyn DialogRunner>>),
+    }
+
+    impl fmt::Debug for DialogBackend {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                DialogBackend::Native => write!(f, "Native"),
+                DialogBackend::Fltk => write!(f, "Fltk"),
+                DialogBackend::Terminal => write!(f, "Terminal"),
+                DialogBackend::Custom(_) => write!(f, "Custom(..)"),
+            }
+        }
+    }
+
+    thread_local! {
+        static DEFAULT_BACKEND: RefCell<Option<DialogBackend>> = const { RefCell::new(None) };
+    }
+
+    /// Sets the file chooser used by every dialog that doesn't pick one with
+    /// `DialogBuilder::backend()`.  FLTK dialogs run on the main thread, so
+    /// the setting belongs to the calling thread.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let app = app::App::default();
+    ///     fltk_set_default_backend(DialogBackend::Fltk);
+    ///
+    ///     // This now shows FLTK's chooser, title and all.
+    ///     match file_fullpath("/home/user/Documents", "Choose a list") {
+    ///         Ok(Some(path)) => println!("Opening {}", path),
+    ///         Ok(None) => println!("The user cancelled."),
+    ///         Err(err) => println!("The dialog failed: {}", err),
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn fltk_set_default_backend(backend: DialogBackend) {
+        DEFAULT_BACKEND.with(|current| *current.borrow_mut() = Some(backend));
+    }
+
+    /// Returns the file chooser used by dialogs that don't pick one themselves.
+    pub fn fltk_default_backend() -> DialogBackend {
+        DEFAULT_BACKEND.with(|current| current.borrow().clone().unwrap_or_default())
+    }
+
+    thread_local! {
+        static LAST_DIRS: RefCell<Option<LastDirs>> = const { RefCell::new(None) };
+    }
+
+    /// Installs the store of last-used directories for the dialogs on this
+    /// thread, or removes it with `None`.
+    ///
+    /// With a store installed, each dialog opens in the directory last
+    /// chosen for its purpose (see `DialogBuilder::purpose()`), falling back
+    /// to the directory the caller passed.  Each successful choice is
+    /// remembered and the store is saved right away.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let app = app::App::default();
+    ///     match LastDirs::load(&LastDirs::default_path("qbnk")) {
+    ///         Ok(lastdirs) => fltk_set_lastdirs(Some(lastdirs)),
+    ///         Err(err) => println!("Starting without remembered directories: {}", err),
+    ///     }
+    ///
+    ///     // Opens where the last list was opened, or in /home/user if there wasn't one.
+    ///     let chosen = DialogBuilder::new(DialogMode::Open)
+    ///         .start_dir("/home/user")
+    ///         .purpose("lists")
+    ///         .show();
+    ///     println!("{:?}", chosen);
+    /// }
+    /// ```
+    ///
+    pub fn fltk_set_lastdirs(lastdirs: Option<LastDirs>) {
+        LAST_DIRS.with(|current| *current.borrow_mut() = lastdirs);
+    }
+
+    /// Returns a copy of the installed store of last-used directories.
+    pub fn fltk_lastdirs() -> Option<LastDirs> {
+        LAST_DIRS.with(|current| current.borrow().clone())
+    }
+
+    /// Fills the `submenu` of a menu bar, such as `"&File/Open &Recent"`, with
+    /// the files in `recent`, newest first, followed by a "Clear List" item.
+    /// The submenu is created if it isn't there yet, and emptied first if it is.
+    ///
+    /// Choosing a file hands `on_open` the same result `file_fullpath` would:
+    /// `Ok(Some(path))` for a file that still exists, which also moves it to
+    /// the top of the list.  A file that has gone missing is dropped from the
+    /// list and reported as `Err(LibFileError::NotFound)`.  Every change to the
+    /// list is saved and the submenu is refilled.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let app = app::App::default();
+    ///     let mut win = window::Window::new(100, 100, 400, 300, "Editor");
+    ///     let mut menubar = menu::MenuBar::new(0, 0, 400, 25, "");
+    ///     win.end();
+    ///     win.show();
+    ///
+    ///     let recent = match RecentFiles::load(&RecentFiles::default_path("qbnk"), 8) {
+    ///         Ok(recent) => Rc::new(RefCell::new(recent)),
+    ///         Err(err) => {
+    ///             println!("{}", err);
+    ///             return;
+    ///         }
+    ///     };
+    ///     let result = fltk_fill_recent_menu(&mut menubar, "&File/Open &Recent", &recent,
+    ///         |chosen| match chosen {
+    ///             Ok(Some(path)) => println!("Opening {}", path),
+    ///             Ok(None) => {},
+    ///             Err(err) => println!("Can't open it: {}", err),
+    ///         });
+    ///     match result {
+    ///         Ok(()) => app.run().unwrap(),
+    ///         Err(err) => println!("{}", err),
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn fltk_fill_recent_menu<F>(menubar: &mut MenuBar, submenu: &str, recent: &Rc<RefCell<RecentFiles>>,
+                                    on_open: F) -> Result<(), LibFileError>
+        where F: FnMut(Result<Option<String>, LibFileError>) + Clone + 'static {
+
+        // region Find or create the submenu, and empty it.
+        let mut index = menubar.find_index(submenu);
+        if index < 0 {
+            menubar.add(submenu, Shortcut::None, MenuFlag::Submenu, |_| {});
+            index = menubar.find_index(submenu);
+        }
+        menubar.clear_submenu(index)?;
+        // endregion
+
+        let paths = recent.borrow().paths().to_vec();
+        if paths.is_empty() {
+            menubar.add(&format!("{}/(No recent files)", submenu), Shortcut::None, MenuFlag::Inactive, |_| {});
+        }
+
+        for (count, path) in paths.iter().enumerate() {
+            let label = format!("{}/{}  {}", submenu, count + 1, fltk_escape_menu_label(path));
+            let flag = match count + 1 == paths.len() {
+                true => MenuFlag::MenuDivider,  // Separates the files from "Clear List".
+                false => MenuFlag::Normal,
+            };
+            let (path, usesub, userecent, mut useopen) =
+                (path.clone(), submenu.to_string(), recent.clone(), on_open.clone());
+
+            menubar.add(&label, Shortcut::None, flag, move |bar| {
+                let exists = Path::new(&path).is_file();
+                {
+                    let mut list = userecent.borrow_mut();
+                    match exists {
+                        true => { let _ = list.add(&path); }
+                        false => list.remove(&path),
+                    }
+                    let _ = list.save();  // A failed save only costs the list's memory of this choice.
+                }
+                fltk_refill_recent_menu(bar, &usesub, &userecent, useopen.clone());
+
+                match exists {
+                    true => useopen(Ok(Some(path.clone()))),
+                    false => useopen(Err(LibFileError::NotFound(path.clone()))),
+                }
+            });
+        }
+
+        let (usesub, userecent, useopen) = (submenu.to_string(), recent.clone(), on_open.clone());
+        menubar.add(&format!("{}/Clear List", submenu), Shortcut::None, MenuFlag::Normal, move |bar| {
+            userecent.borrow_mut().clear();
+            let _ = userecent.borrow().save();
+            fltk_refill_recent_menu(bar, &usesub, &userecent, useopen.clone());
+        });
+
+        Ok(())
+    }
+
+    /// Refills the recent-files submenu once the current menu callback has
+    /// finished.  The item whose callback is running can't be deleted from
+    /// inside that callback.
+    fn fltk_refill_recent_menu<F>(bar: &MenuBar, submenu: &str, recent: &Rc<RefCell<RecentFiles>>, on_open: F)
+        where F: FnMut(Result<Option<String>, LibFileError>) + Clone + 'static {
+        let (mut usebar, usesub, userecent) = (bar.clone(), submenu.to_string(), recent.clone());
+        app::add_timeout3(0.0, move |_| {
+            let _ = fltk_fill_recent_menu(&mut usebar, &usesub, &userecent, on_open.clone());
+        });
+    }
+
+    /// Escapes the characters FLTK treats specially in menu labels, so a path
+    /// shows up as a single item.
+    fn fltk_escape_menu_label(text: &str) -> String {
+        let mut escaped = String::new();
+        for ch in text.chars() {
+            match ch {
+                '\\' | '/' | '_' => {
+                    escaped.push('\\');
+                    escaped.push(ch);
+                }
+                '&' => escaped.push_str("&&"),
+                _ => escaped.push(ch),
+            }
+        }
+        escaped
+    }
+
+    /// Checks whether a graphical display is there to show dialogs on.
+    ///
+    /// Windows and macOS always have one.  Elsewhere, a display is assumed
+    /// only if `DISPLAY` or `WAYLAND_DISPLAY` is set, which isn't the case
+    /// over a plain SSH session.
+    pub fn fltk_display_available() -> bool {
+        if cfg!(any(target_os = "windows", target_os = "macos")) {
+            return true;
+        }
+        ["DISPLAY", "WAYLAND_DISPLAY"].iter().any(|var| match env::var(var) {
+            Ok(value) => !value.is_empty(),
+            Err(_) => false,
+        })
+    }
+
+    /// Turns `widget` into a target for files dragged in from a file manager.
+    ///
+    /// Each drop is read with `fltk_parse_dropped_paths()`.  Paths that aren't
+    /// existing files, or that match none of `filters`, are dropped quietly
+    /// (an empty set lets every file through).  If any paths are left,
+    /// `on_drop` gets them, in the order they were dragged.
+    ///
+    /// This sets the widget's `handle()` closure, so it replaces any handler
+    /// the widget already had.  Windows and groups work as targets, but a
+    /// child widget under the pointer gets the drop first.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let app = app::App::default();
+    ///     let mut win = Window::new(100, 100, 400, 300, "Drop banks here");
+    ///     win.end();
+    ///     win.show();
+    ///
+    ///     let filters = FilterSet::new(&[("Banks", "*.bnk")])
+    ///         .expect("The filters are written correctly.");
+    ///     fltk_make_drop_target(&mut win, &filters, |paths| {
+    ///         for path in paths {
+    ///             println!("Dropped {}", path.display());
+    ///         }
+    ///     });
+    ///
+    ///     app.run().unwrap();
+    /// }
+    /// ```
+    ///
+    pub fn fltk_make_drop_target<W, F>(widget: &mut W, filters: &FilterSet, mut on_drop: F)
+    where
+        W: WidgetBase,
+        F: FnMut(Vec<PathBuf>) + 'static,
+    {
+        let filters = filters.clone();
+        widget.handle(move |_, event| match event {
+            // Saying yes to these is how a widget accepts a drag.
+            Event::DndEnter | Event::DndDrag | Event::DndLeave | Event::DndRelease => true,
+            Event::Paste => {
+                let mut paths = fltk_parse_dropped_paths(&app::event_text());
+                paths.retain(|path| path.is_file() && (filters.is_empty()
+                        || filters.entries().iter().any(|entry| entry.matches(&path.to_string_lossy()))));
+                if !paths.is_empty() {
+                    on_drop(paths);
+                }
+                true
+            }
+            _ => false,
+        });
+    }
+
+    /// Reads the paths out of a drag-and-drop payload.
+    ///
+    /// Handles what file managers actually send:  one entry per line,
+    /// `file://` URIs (with or without a host) and `file:/` URIs with their
+    /// `%20`-style escapes, and plain paths.  Blank lines, `#` comment lines and other kinds of URI
+    /// (`http://` and the like) are skipped.  The paths are not checked.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let payload = "file:///home/user/banks/Algebra%201.bnk\r\nfile:///home/user/banks/geometry.bnk\r\n";
+    ///     for path in fltk_parse_dropped_paths(payload) {
+    ///         println!("{}", path.display());  // /home/user/banks/Algebra 1.bnk, then geometry.bnk
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn fltk_parse_dropped_paths(payload: &str) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+
+        for line in payload.lines() {
+            let line = line.trim_matches(|ch: char| ch == '\0' || ch.is_whitespace());
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            // region Strip the `file:` scheme and any host, then undo the escapes.
+            let path = match line.get(..5) {
+                Some(scheme) if scheme.eq_ignore_ascii_case("file:") => {
+                    let rest = match line[5..].strip_prefix("//") {
+                        Some(authority) => match authority.find('/') {
+                            Some(slash) => &authority[slash..],  // Drops `localhost` or another host name.
+                            None => continue,
+                        },
+                        None if line[5..].starts_with('/') => &line[5..],  // KDE sends `file:/path`.
+                        None => continue,
+                    };
+                    // `file:///C:/dir` is `C:/dir` on Windows.
+                    let rest = match cfg!(target_os = "windows") && rest.get(2..3) == Some(":") {
+                        true => &rest[1..],
+                        false => rest,
+                    };
+                    fltk_percent_decode(rest)
+                }
+                _ if line.contains("://") => continue,
+                _ => line.to_string(),
+            };
+            // endregion
+
+            paths.push(PathBuf::from(path));
+        }
+        paths
+    }
+
+    /// Undoes URI `%XX` escapes.  A `%` that isn't followed by two hex digits
+    /// is kept as it is.
+    fn fltk_percent_decode(text: &str) -> String {
+        let bytes = text.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut idx = 0;
+
+        while idx < bytes.len() {
+            let escaped = match bytes[idx] == b'%' {
+                true => text.get(idx + 1..idx + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+                false => None,
+            };
+            match escaped {
+                Some(byte) => {
+                    decoded.push(byte);
+                    idx += 3;
+                }
+                None => {
+                    decoded.push(bytes[idx]);
+                    idx += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&decoded).into_owned()
+    }
+
+    /// Everything a `DialogRunner` is asked to show, after `DialogBuilder`
+    /// has done its part:  `startdir` has already fallen back to the home
+    /// directory if needed, and `presetname` already carries the default
+    /// extension when saving.
+    #[derive(Debug, Clone)]
+    pub struct DialogRequest {
+        pub mode: DialogMode,
+        pub startdir: String,
+        pub presetname: String,
+        pub filters: FilterSet,
+        pub title: String,
+        pub preview: bool,
+        pub parent: Option<Window>,
+    }
+
+    /// What a `DialogRunner` hands back when the user chooses something.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct DialogPick {
+        /// The chosen path(s).  Never empty.
+        pub paths: Vec<PathBuf>,
+        /// The index in the request's `FilterSet` of the filter the user
+        /// picked, or `None` if it wasn't one of them or the chooser can't
+        /// say which it was.
+        pub filterindex: Option<usize>,
+    }
+
+    /// Shows one file dialog.  `DialogBuilder` does the path resolution,
+    /// extension handling and overwrite checks; the runner only shows the
+    /// chooser and reports what the user did.
+    ///
+    /// Returns `Ok(Some(pick))` for a choice, `Ok(None)` if the user
+    /// cancelled, and `Err` if the chooser failed.
+    pub trait DialogRunner {
+        fn run(&mut self, request: &DialogRequest) -> Result<Option<DialogPick>, LibFileError>;
+
+        /// Tells the user why `fullpath` was turned down by the validator set
+        /// with `DialogBuilder::validate()`, before the dialog opens again.
+        /// The default shows an alert.
+        fn show_rejection(&mut self, fullpath: &str, reason: &str) {
+            fltk_show_rejection(fullpath, reason, false);
+        }
+    }
+
+    /// Collects the settings for a file dialog in one place, then shows it.
+    ///
+    /// Start with `DialogBuilder::new()`, chain the settings you care about,
+    /// and finish with `show()` (or `show_multi()` for `DialogMode::Multi`).
+    /// To keep the event loop running instead of waiting for the user, finish
+    /// with `show_async()`, `show_multi_async()` or `send_to()`.
+    /// Anything left unset keeps its default:  no title, no preset name,
+    /// no filters, no parent window, the home directory as the start
+    /// directory, and full paths returned.
+    ///
+    /// The dialog functions below (`file_fullpath`, `file_browse_tosave`, etc.)
+    /// are thin wrappers around this builder.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let app = app::App::default();
+    ///     let filters = FilterSet::new(&[("Lists", "*.lst"), ("Variables", "*.vrbl")])
+    ///         .expect("The filters are written correctly.");
+    ///
+    ///     let result = DialogBuilder::new(DialogMode::Save)
+    ///         .start_dir("/home/user/qbnk_data/lists")
+    ///         .preset_name("new_list")
+    ///         .filters(&filters)
+    ///         .title("Save the list")
+    ///         .show();
+    ///
+    ///     match result {
+    ///         Ok(Some(path)) => println!("Saving to {}", path),
+    ///         Ok(None) => println!("The user cancelled."),
+    ///         Err(err) => println!("The dialog failed: {}", err),
+    ///     }
+    /// }
+    /// ```
+    ///
+    #[derive(Debug, Clone)]
+    pub struct DialogBuilder {
+        mode: DialogMode,
+        startdir: String,
+        presetname: String,
+        filters: FilterSet,
+        title: String,
+        parent: Option<Window>,
+        shape: ReturnShape,
+        enforceext: bool,
+        confirmoverwrite: bool,
+        backend: Option<DialogBackend>,
+        preview: bool,
+        purpose: String,
+        validator: Option<Validator>,
+    }
+
+    impl DialogBuilder {
+        /// Creates a builder for the given kind of dialog with every other setting at its default.
+        pub fn new(mode: DialogMode) -> DialogBuilder {
+            DialogBuilder {
+                mode,
+                startdir: String::new(),
+                presetname: String::new(),
+                filters: FilterSet::default(),
+                title: String::new(),
+                parent: None,
+                shape: ReturnShape::FullPath,
+                enforceext: true,
+                confirmoverwrite: false,
+                backend: None,
+                preview: false,
+                purpose: String::new(),
+                validator: None,
+            }
+        }
+
+        /// Sets the directory the dialog opens in.  A path to a file opens in that
+        /// file's directory.  If the path cannot be resolved, the dialog opens in
+        /// the user's home directory.
+        pub fn start_dir(mut self, sggstdpath: &str) -> DialogBuilder {
+            self.startdir = sggstdpath.to_string();
+            self
+        }
+
+        /// Sets the file name that is filled in when the dialog opens.  In
+        /// `DialogMode::Save`, the extension of the default filter is added to it
+        /// if it doesn't already have an extension.
+        pub fn preset_name(mut self, sggstdname: &str) -> DialogBuilder {
+            self.presetname = sggstdname.to_string();
+            self
+        }
+
+        /// Sets the filters the user can choose between.  The set's default
+        /// entry is selected when the dialog opens.
+        pub fn filters(mut self, filters: &FilterSet) -> DialogBuilder {
+            self.filters = filters.clone();
+            self
+        }
+
+        /// Sets the title of the dialog window.  Note that some native save
+        /// dialogs (Linux in particular) ignore the title.  `DialogBackend::Fltk`
+        /// always shows it.
+        pub fn title(mut self, wintitle: &str) -> DialogBuilder {
+            self.title = wintitle.to_string();
+            self
+        }
+
+        /// Sets the kind of dialog.
+        pub fn mode(mut self, mode: DialogMode) -> DialogBuilder {
+            self.mode = mode;
+            self
+        }
+
+        /// Ties the dialog to a parent window so it opens on top of that
+        /// window, modal to it.  Passing `None` leaves the dialog untied.
+        ///
+        /// `DialogBackend::Fltk` centres its chooser over the parent and makes
+        /// it modal and transient for it.  A native chooser belongs to the
+        /// platform and can't be made modal, so a dialog with a parent uses
+        /// `DialogBackend::Fltk` unless `backend()` or
+        /// `fltk_set_default_backend()` picked another chooser.  A native
+        /// chooser picked that way is only raised over the parent.  See
+        /// `fltk_show_on_parent()`.
+        pub fn parent(mut self, parent: Option<&Window>) -> DialogBuilder {
+            self.parent = parent.cloned();
+            self
+        }
+
+        /// Sets what is returned for each chosen path.
+        pub fn shape(mut self, shape: ReturnShape) -> DialogBuilder {
+            self.shape = shape;
+            self
+        }
+
+        /// In `DialogMode::Save`, makes sure the returned name carries the
+        /// extension of the filter the user picked in the dialog.  If the typed
+        /// name doesn't match that filter, the filter's extension is added to it.
+        /// "All Files" is left alone, and so is the name if the chooser can't
+        /// say which filter was picked.  This is on by default.
+        ///
+        /// The chooser only checked the typed name for overwriting.  If the
+        /// name with the extension added is a file that already exists, the
+        /// user is asked about it as with `confirm_overwrite(true)`, even
+        /// when that is off.
+        pub fn enforce_extension(mut self, enforce: bool) -> DialogBuilder {
+            self.enforceext = enforce;
+            self
+        }
+
+        /// In `DialogMode::Save`, asks before handing back a file that already
+        /// exists.  The user can overwrite it, keep both (the new file gets a
+        /// free numbered name such as `notes (1).lst`), or choose again.  Closing
+        /// the question counts as cancelling.  This is off by default.
+        pub fn confirm_overwrite(mut self, confirm: bool) -> DialogBuilder {
+            self.confirmoverwrite = confirm;
+            self
+        }
+
+        /// Chooses which file chooser this dialog uses.  Without this call the
+        /// dialog uses whatever `fltk_set_default_backend()` last set, which is
+        /// `DialogBackend::Native` unless changed.  Either way, `Native` and
+        /// `Fltk` fall back to `Terminal` when there is no display.
+        pub fn backend(mut self, backend: DialogBackend) -> DialogBuilder {
+            self.backend = Some(backend);
+            self
+        }
+
+        /// Names what the dialog is for, such as `"banks"` or `"exports"`, so the
+        /// store installed with `fltk_set_lastdirs()` can remember a directory
+        /// for it.  Without a purpose, the mode's name is used (`"open"`,
+        /// `"save"`, `"dir"` or `"multi"`).
+        pub fn purpose(mut self, purpose: &str) -> DialogBuilder {
+            self.purpose = purpose.to_string();
+            self
+        }
+
+        /// Shows a preview of the highlighted file, where the chooser can.
+        /// `DialogBackend::Fltk` always can; native choosers vary.
+        pub fn preview(mut self, preview: bool) -> DialogBuilder {
+            self.preview = preview;
+            self
+        }
+
+        /// Checks each chosen path with `validator` before handing it back.  If
+        /// the validator rejects a path, the reason is shown in an alert (or
+        /// printed, in the terminal) and the dialog opens again in the same
+        /// directory, until the user picks a path that passes or cancels.
+        /// Validators see full paths, whatever `shape()` is set to.
+        ///
+        /// # Example
+        ///
+        /// ```ignore
+        /// fn main() {
+        ///     let app = app::App::default();
+        ///     let result = DialogBuilder::new(DialogMode::Open)
+        ///         .start_dir("/home/user/qbnk_data/lists")
+        ///         .validate(|path| match file_read_to_string(path) {
+        ///             Ok(text) if text.starts_with("#LIST") => Validation::Accept,
+        ///             Ok(_) => Validation::Reject("That file isn't a list.".to_string()),
+        ///             Err(err) => Validation::Reject(err.to_string()),
+        ///         })
+        ///         .show();
+        ///
+        ///     match result {
+        ///         Ok(Some(path)) => println!("Opening {}", path),
+        ///         Ok(None) => println!("The user cancelled."),
+        ///         Err(err) => println!("The dialog failed: {}", err),
+        ///     }
+        /// }
+        /// ```
+        ///
+        pub fn validate<F: Fn(&str) -> Validation + 'static>(mut self, validator: F) -> DialogBuilder {
+            self.validator = Some(Validator(Rc::new(validator)));
+            self
+        }
+
+        /// Shows the dialog and returns the path the user chose, shaped as
+        /// set by `shape()`.  Returns `Ok(None)` if the user cancelled.
+        /// In `DialogMode::Multi`, only the first chosen path is returned;
+        /// use `show_multi()` to get all of them.
+        pub fn show(&self) -> Result<Option<String>, LibFileError> {
+            match self.show_multi()? {
+                Some(mut chosen) => Ok(Some(chosen.remove(0))),
+                None => Ok(None),
+            }
+        }
+
+        /// Shows the dialog and returns every path the user chose, shaped as
+        /// set by `shape()`.  Returns `Ok(None)` if the user cancelled.  The
+        /// returned vector is never empty.
+        pub fn show_multi(&self) -> Result<Option<Vec<String>>, LibFileError> {
+            let backend = self.usebackend();
+            let purpose = self.usepurpose();
+            let mut startdir = match fltk_remembered_dir(&purpose) {
+                Some(dir) => dir,
+                None => self.startdir.clone(),
+            };
+            let mut presetname = self.presetname.clone();
+
+            // Show the chooser until the user settles on a path that passes, or cancels.
+            loop {
+                let request = self.dialog_request(&startdir, &presetname);
+                let pick = self.with_runner(&backend, |runner| runner.run(&request))?;
+                match self.settle(&backend, pick, &mut startdir, &mut presetname)? {
+                    Settled::Accept(fullpaths) => return self.finish(&purpose, fullpaths),
+                    Settled::ChooseAgain => continue,
+                    Settled::Cancel => return Ok(None),
+                }
+            }
+        }
+
+        /// Returns at once and shows the dialog from the event loop instead,
+        /// handing the result of `show()` to `on_done` when the user is
+        /// finished.  The app's event loop (`app.run()` or `app::wait()`) must
+        /// be running for the dialog to appear.
+        ///
+        /// The dialog always uses FLTK's own chooser here, even if
+        /// `DialogBackend::Native` is set, because native choosers only
+        /// return once they close.  FLTK's chooser is shown and then watched
+        /// from a timeout, so other windows' timers (an autosave, say) keep
+        /// running while it is open.  The overwrite question and rejection
+        /// messages are FLTK's usual modal messages, which keep the timers
+        /// running too.  `DialogBackend::Terminal` and `DialogBackend::Custom`
+        /// have no such chooser: they run from the event loop as `show()`
+        /// would, and the terminal holds up the loop until it is answered.
+        ///
+        /// # Example
+        ///
+        /// ```ignore
+        /// fn main() {
+        ///     let app = app::App::default();
+        ///     let mut win = Window::new(100, 100, 400, 300, "Editor");
+        ///     win.end();
+        ///     win.show();
+        ///
+        ///     DialogBuilder::new(DialogMode::Open)
+        ///         .start_dir("/home/user/qbnk_data/lists")
+        ///         .show_async(|result| match result {
+        ///             Ok(Some(path)) => println!("Opening {}", path),
+        ///             Ok(None) => println!("The user cancelled."),
+        ///             Err(err) => println!("The dialog failed: {}", err),
+        ///         });
+        ///
+        ///     app.run().unwrap();
+        /// }
+        /// ```
+        ///
+        pub fn show_async<F>(self, on_done: F)
+        where
+            F: FnOnce(Result<Option<String>, LibFileError>) + 'static,
+        {
+            self.show_multi_async(move |result| on_done(match result {
+                Ok(Some(mut chosen)) => Ok(Some(chosen.remove(0))),
+                Ok(None) => Ok(None),
+                Err(err) => Err(err),
+            }));
+        }
+
+        /// Same as `show_async()`, but hands `on_done` every path the user
+        /// chose, as `show_multi()` would return them.
+        pub fn show_multi_async<F>(self, on_done: F)
+        where
+            F: FnOnce(Result<Option<Vec<String>>, LibFileError>) + 'static,
+        {
+            match self.usebackend() {
+                DialogBackend::Native | DialogBackend::Fltk => {
+                    let purpose = self.usepurpose();
+                    let startdir = match fltk_remembered_dir(&purpose) {
+                        Some(dir) => dir,
+                        None => self.startdir.clone(),
+                    };
+                    let presetname = self.presetname.clone();
+                    fltk_watch_chooser(self, purpose, startdir, presetname, on_done);
+                }
+                _ => fltk_defer_once(move || on_done(self.show_multi())),
+            }
+        }
+
+        /// Same as `show_async()`, but sends the result down an
+        /// `app::channel()` as a message made by `wrap`, for apps that handle
+        /// their events in one `match` on `receiver.recv()`.
+        ///
+        /// # Example
+        ///
+        /// ```ignore
+        /// enum Message { Opened(Option<String>), Failed(String) }
+        ///
+        /// fn main() {
+        ///     let app = app::App::default();
+        ///     let (sender, receiver) = app::channel::<Message>();
+        ///
+        ///     DialogBuilder::new(DialogMode::Open).send_to(sender, |result| match result {
+        ///         Ok(path) => Message::Opened(path),
+        ///         Err(err) => Message::Failed(err.to_string()),
+        ///     });
+        ///
+        ///     while app.wait() {
+        ///         match receiver.recv() {
+        ///             Some(Message::Opened(Some(path))) => println!("Opening {}", path),
+        ///             Some(Message::Opened(None)) => println!("The user cancelled."),
+        ///             Some(Message::Failed(err)) => println!("The dialog failed: {}", err),
+        ///             None => {},
+        ///         }
+        ///     }
+        /// }
+        /// ```
+        ///
+        pub fn send_to<T, F>(self, sender: app::Sender<T>, wrap: F)
+        where
+            T: Send + Sync + 'static,
+            F: FnOnce(Result<Option<String>, LibFileError>) -> T + 'static,
+        {
+            self.show_async(move |result| sender.send(wrap(result)));
+        }
+
+        /// The request for one showing of the chooser.
+        fn dialog_request(&self, startdir: &str, presetname: &str) -> DialogRequest {
+            DialogRequest {
+                mode: self.mode,
+                startdir: dir_resolve_or_home(startdir),
+                presetname: match presetname.is_empty() {
+                    true => String::new(),
+                    false => self.usename(presetname),
+                },
+                filters: self.filters.clone(),
+                title: self.title.clone(),
+                preview: self.preview,
+                parent: self.parent.clone(),
+            }
+        }
+
+        /// Checks what the user picked from one showing of the chooser: adds
+        /// the picked filter's extension if `enforce_extension` is on, asks
+        /// about overwriting and runs the validator.  If the chooser should be
+        /// shown again, `startdir` and `presetname` are moved to where it
+        /// should open.
+        fn settle(&self, backend: &DialogBackend, pick: Option<DialogPick>, startdir: &mut String,
+                  presetname: &mut String) -> Result<Settled, LibFileError> {
+            let pick = match pick {
+                Some(pick) if !pick.paths.is_empty() => pick,
+                _ => return Ok(Settled::Cancel),
+            };
+
+            // region Find the filter the user picked, if the extension needs enforcing.
+            let pickedfilter = match pick.filterindex {
+                Some(index) if self.mode == DialogMode::Save && self.enforceext => {
+                    self.filters.entries().get(index)
+                }
+                _ => None,
+            };
+            // endregion
+
+            let mut fullpaths = Vec::new();
+            let mut extended = false;
+            for path in pick.paths {
+                let fullpath = fltk_path_to_string(&path)?;
+                let usepath = match pickedfilter {
+                    Some(entry) => entry.add_extension(&fullpath),
+                    None => fullpath.clone(),
+                };
+                extended |= usepath != fullpath;
+                fullpaths.push(usepath);
+            }
+
+            // The chooser only checked the name the user typed, not the one with the extension added.
+            let confirm = self.confirmoverwrite || extended;
+            let interm = matches!(backend, DialogBackend::Terminal);
+            let fullpaths = match self.mode == DialogMode::Save && confirm {
+                false => fullpaths,
+                true => match fltk_confirm_overwrite(&fullpaths[0], interm)? {
+                    OverwriteChoice::Use(fullpath) => vec![fullpath],
+                    OverwriteChoice::ChooseAgain => {
+                        (*startdir, *presetname) = fltk_choose_again_from(&fullpaths[0], startdir.clone())?;
+                        return Ok(Settled::ChooseAgain);
+                    }
+                    OverwriteChoice::Cancel => return Ok(Settled::Cancel),
+                },
+            };
+
+            let rejected = match &self.validator {
+                Some(Validator(validator)) => fullpaths.iter()
+                    .find_map(|fullpath| match validator(fullpath) {
+                        Validation::Accept => None,
+                        Validation::Reject(reason) => Some((fullpath.clone(), reason)),
+                    }),
+                None => None,
+            };
+            match rejected {
+                None => Ok(Settled::Accept(fullpaths)),
+                Some((fullpath, reason)) => {
+                    self.with_runner(backend, |runner| runner.show_rejection(&fullpath, &reason));
+                    (*startdir, *presetname) = fltk_choose_again_from(&fullpath, startdir.clone())?;
+                    if self.mode != DialogMode::Save {
+                        *presetname = self.presetname.clone();
+                    }
+                    Ok(Settled::ChooseAgain)
+                }
+            }
+        }
+
+        /// Remembers the directory of the accepted paths and shapes them as
+        /// set by `shape()`.
+        fn finish(&self, purpose: &str, fullpaths: Vec<String>) -> Result<Option<Vec<String>>, LibFileError> {
+            fltk_remember_dir(purpose, self.mode, &fullpaths[0]);
+
+            let mut shaped = Vec::new();
+            for fullpath in fullpaths {
+                match self.shape {
+                    ReturnShape::FullPath => shaped.push(fullpath),
+                    ReturnShape::NameOnly => shaped.push(fltk_path_to_fname(Path::new(&fullpath))?),
+                }
+            }
+            Ok(Some(shaped))
+        }
+
+        /// Hands the runner behind `backend` to `task`.
+        fn with_runner<T>(&self, backend: &DialogBackend, task: impl FnOnce(&mut dyn DialogRunner) -> T) -> T {
+            match backend {
+                DialogBackend::Native => task(&mut NativeRunner),
+                DialogBackend::Fltk => task(&mut FltkRunner),
+                DialogBackend::Terminal => task(&mut TerminalRunner),
+                DialogBackend::Custom(runner) => task(&mut *runner.borrow_mut()),
+            }
+        }
+
+        /// The purpose the last-used directory is remembered under.
+        fn usepurpose(&self) -> String {
+            match (self.purpose.is_empty(), self.mode) {
+                (false, _) => self.purpose.clone(),
+                (true, DialogMode::Open) => "open".to_string(),
+                (true, DialogMode::Save) => "save".to_string(),
+                (true, DialogMode::Dir) => "dir".to_string(),
+                (true, DialogMode::Multi) => "multi".to_string(),
+            }
+        }
+
+        /// The backend this dialog uses, after falling back to the terminal
+        /// if there is no display for a graphical chooser.
+        fn usebackend(&self) -> DialogBackend {
+            let backend = match &self.backend {
+                Some(backend) => backend.clone(),
+                None => DEFAULT_BACKEND.with(|current| match (current.borrow().as_ref(), &self.parent) {
+                    (Some(backend), _) => backend.clone(),
+                    (None, Some(_)) => DialogBackend::Fltk,  // Only FLTK's chooser can be modal to the parent.
+                    (None, None) => DialogBackend::Native,
+                }),
+            };
+            match backend {
+                DialogBackend::Native | DialogBackend::Fltk if !fltk_display_available() => {
+                    DialogBackend::Terminal
+                }
+                _ => backend,
+            }
+        }
+
+        /// The preset name, with the default extension added when saving.
+        fn usename(&self, presetname: &str) -> String {
+            let (has_ext, _) = file_check_extension(presetname);
+            let ext_to_append = match self.filters.default_entry() {
+                Some(entry) => entry.default_extension(),
+                None => None,
+            };
+
+            match ext_to_append {
+                Some(extnsn) if self.mode == DialogMode::Save && !has_ext => {
+                    format!("{}.{}", presetname, extnsn)
+                }
+                _ => presetname.to_string(),
+            }
+        }
+    }
+
+    /// Runs the platform's native chooser.
+    struct NativeRunner;
+
+    impl DialogRunner for NativeRunner {
+        fn run(&mut self, request: &DialogRequest) -> Result<Option<DialogPick>, LibFileError> {
+
+            // region Set up the dialog browser.
+            let choosertype = match request.mode {
+                DialogMode::Open => dialog::NativeFileChooserType::BrowseFile,
+                DialogMode::Save => dialog::NativeFileChooserType::BrowseSaveFile,
+                DialogMode::Dir => dialog::NativeFileChooserType::BrowseDir,
+                DialogMode::Multi => dialog::NativeFileChooserType::BrowseMultiFile,
+            };
+            let mut fchooser = dialog::NativeFileChooser::new(choosertype);
+            fchooser.set_directory(&request.startdir)?;
+
+            if !request.title.is_empty() {
+                fchooser.set_title(&request.title);
+            }
+            if !request.filters.is_empty() {
+                fchooser.set_filter(&fltk_build_dialogfltr(&request.filters));
+                fchooser.set_filter_value(request.filters.default_index() as i32);
+            }
+            if !request.presetname.is_empty() {
+                fchooser.set_preset_file(&request.presetname);
+            }
+            if request.preview {
+                fchooser.set_option(dialog::NativeFileChooserOptions::Preview);
+            }
+            // endregion
+
+            let chosen = match fltk_show_on_parent(&mut fchooser, request.parent.as_ref())? {
+                Some(chosen) => chosen,
+                None => return Ok(None),
+            };
+
+            let filterindex = match usize::try_from(fchooser.filter_value()) {
+                Ok(index) if index < request.filters.entries().len() => Some(index),
+                _ => None,  // The backend didn't say, so don't guess.
+            };
+            Ok(Some(DialogPick { paths: chosen, filterindex }))
+        }
+    }
+
+    /// Runs FLTK's own `dialog::FileChooser`, which looks and behaves the
+    /// same on every desktop.  If the user picks one of FLTK's own filters
+    /// ("All Files" or "Custom Filter"), no filter index is reported.
+    struct FltkRunner;
+
+    impl DialogRunner for FltkRunner {
+        fn run(&mut self, request: &DialogRequest) -> Result<Option<DialogPick>, LibFileError> {
+            let mut fchooser = fltk_open_chooser(request);
+            while fchooser.shown() {
+                app::wait();
+            }
+            Ok(fltk_chooser_pick(&mut fchooser, request))
+        }
+    }
+
+    /// Asks in the terminal, using the prompts from `dir_mngmnt` and
+    /// `file_mngmnt`.  Files are picked from a numbered menu and names are
+    /// typed in.  Only the default filter of the request is applied.  In
+    /// `DialogMode::Multi` the menu comes back after each pick, so files
+    /// can be added and removed until the user picks "(Done)".
+    struct TerminalRunner;
+
+    impl DialogRunner for TerminalRunner {
+        fn run(&mut self, request: &DialogRequest) -> Result<Option<DialogPick>, LibFileError> {
+            if !request.title.is_empty() {
+                println!("\n {}", request.title);
+            }
+            let mut startdir = request.startdir.clone();
+
+            // A directory with nothing to pick in it sends the user back to choose another.
+            let chosen = loop {
+
+                // region Settle on a directory.  A blank answer keeps the start directory.
+                let prompt = format!("\n Please enter a directory [{}]:  ", startdir);
+                let typed = match input_string_prompt(&prompt) {
+                    typed if typed.is_empty() => startdir.clone(),
+                    typed => typed,
+                };
+                let usedir = match dir_checkexist_fix(&typed) {
+                    Ok(usedir) => usedir,
+                    Err(LibFileError::Cancelled) => return Ok(None),
+                    Err(err) => return Err(err),
+                };
+                if !Path::new(&usedir).is_dir() {
+                    println!("\n {} \n is a file, not a directory.  Please choose another.", usedir);
+                    continue;
+                }
+                // endregion
+
+                match request.mode {
+                    DialogMode::Dir => break vec![PathBuf::from(usedir)],
+                    DialogMode::Save => {
+                        let prompt = match request.presetname.is_empty() {
+                            true => "\n Please enter a name for your file (blank to cancel):  ".to_string(),
+                            false => format!("\n Please enter a name for your file [{}]:  ", request.presetname),
+                        };
+                        let fname = match input_string_prompt(&prompt) {
+                            typed if typed.is_empty() => request.presetname.clone(),
+                            typed => typed,
+                        };
+                        if fname.is_empty() {
+                            return Ok(None);
+                        }
+                        break vec![Path::new(&usedir).join(fname)];
+                    }
+                    DialogMode::Open | DialogMode::Multi => {
+                        let mut fnames = file_get_dir_list(&usedir)?;
+                        let keeper = request.filters.default_entry();
+                        fnames.retain(|fname| match keeper {
+                            Some(entry) => entry.matches(fname),
+                            None => true,
+                        });
+                        if fnames.is_empty() {
+                            println!("\n There are no matching files in that directory.  Please choose another.");
+                            startdir = usedir;
+                            continue;
+                        }
+                        fnames.sort();
+
+                        let picked = match request.mode {
+                            DialogMode::Multi => fltk_terminal_pick_many(&fnames),
+                            _ => fltk_terminal_pick_one(&fnames),
+                        };
+                        match picked {
+                            Some(picked) => break picked.iter().map(|fname| Path::new(&usedir).join(fname)).collect(),
+                            None => return Ok(None),
+                        }
+                    }
+                }
+            };
+
+            Ok(Some(DialogPick { paths: chosen, filterindex: Some(request.filters.default_index()) }))
+        }
+
+        fn show_rejection(&mut self, fullpath: &str, reason: &str) {
+            fltk_show_rejection(fullpath, reason, true);
+        }
+    }
+
+    /// Picks one of `fnames` from a numbered menu.  Returns `None` if the
+    /// user picks "(Cancel)".
+    fn fltk_terminal_pick_one(fnames: &[String]) -> Option<Vec<String>> {
+        let mut opts = fnames.to_vec();
+        opts.push("(Cancel)".to_string());
+
+        let choice = util_activity_menu(&opts, "\n Please choose which file you want to use \n");
+        match choice == opts.len() {
+            true => None,
+            false => Some(vec![fnames[choice - 1].clone()]),
+        }
+    }
+
+    /// Picks any number of `fnames` from a numbered menu.  Each pick adds or
+    /// removes a file, and the menu comes back until the user picks "(Done)".
+    /// Returns `None` if the user picks "(Cancel)", or "(Done)" with nothing picked.
+    fn fltk_terminal_pick_many(fnames: &[String]) -> Option<Vec<String>> {
+        let mut picked = vec![false; fnames.len()];
+
+        loop {
+            let mut opts: Vec<String> = fnames.iter().zip(&picked)
+                .map(|(fname, on)| match on {
+                    true => format!("[x] {}", fname),
+                    false => format!("[ ] {}", fname),
+                })
+                .collect();
+            opts.push("(Done)".to_string());
+            opts.push("(Cancel)".to_string());
+
+            let choice = util_activity_menu(&opts, "\n Choose the files you want to use, then choose (Done) \n");
+            match choice {
+                choice if choice == fnames.len() + 1 => break,
+                choice if choice == fnames.len() + 2 => return None,
+                choice => picked[choice - 1] = !picked[choice - 1],
+            }
+        }
+
+        let chosen: Vec<String> = fnames.iter().zip(&picked)
+            .filter(|(_, on)| **on)
+            .map(|(fname, _)| fname.clone())
+            .collect();
+        match chosen.is_empty() {
+            true => None,
+            false => Some(chosen),
+        }
+    }
+
+    /// One preset answer for a `ScriptedRunner`.
+    #[derive(Debug)]
+    pub enum ScriptedAnswer {
+        /// The user chose these paths with the request's default filter.
+        Chosen(Vec<String>),
+        /// The user chose these paths with the filter at this index.
+        ChosenWithFilter(Vec<String>, usize),
+        /// The user chose these paths, and the chooser couldn't say with
+        /// which filter.
+        ChosenUnknownFilter(Vec<String>),
+        /// The user cancelled.
+        Cancel,
+        /// The chooser failed with this error.
+        Error(LibFileError),
+    }
+
+    /// A `DialogRunner` that shows nothing.  It hands back preset answers in
+    /// order and records every request it receives, so dialog-driven code
+    /// can be checked without a display.
+    ///
+    /// Keep a clone of the `Rc` to look at the requests, and the reasons any
+    /// validator gave for turning paths down, afterwards.  Once the answers
+    /// run out, every further request fails with `LibFileError::Dialog`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let runner = Rc::new(RefCell::new(ScriptedRunner::new(vec![
+    ///         ScriptedAnswer::Chosen(vec!["/tmp/report".to_string()]),
+    ///     ])));
+    ///     let filters = FilterSet::new(&[("Lists", "*.lst")])
+    ///         .expect("The filters are written correctly.");
+    ///
+    ///     let chosen = DialogBuilder::new(DialogMode::Save)
+    ///         .start_dir("/no/such/dir")
+    ///         .preset_name("report")
+    ///         .filters(&filters)
+    ///         .backend(DialogBackend::Custom(runner.clone()))
+    ///         .show();
+    ///
+    ///     println!("{:?}", chosen);                                 // Ok(Some("/tmp/report.lst"))
+    ///     println!("{}", runner.borrow().requests()[0].startdir);    // The home directory.
+    ///     println!("{}", runner.borrow().requests()[0].presetname);  // report.lst
+    /// }
+    /// ```
+    ///
+    #[derive(Debug, Default)]
+    pub struct ScriptedRunner {
+        answers: VecDeque<ScriptedAnswer>,
+        requests: Vec<DialogRequest>,
+        rejections: Vec<String>,
+    }
+
+    impl ScriptedRunner {
+        /// Creates a runner that gives `answers` in order.
+        pub fn new(answers: Vec<ScriptedAnswer>) -> ScriptedRunner {
+            ScriptedRunner { answers: answers.into(), requests: Vec::new(), rejections: Vec::new() }
+        }
+
+        /// Adds one more answer to the end of the script.
+        pub fn push_answer(&mut self, answer: ScriptedAnswer) {
+            self.answers.push_back(answer);
+        }
+
+        /// Every request received so far, oldest first.
+        pub fn requests(&self) -> &[DialogRequest] {
+            &self.requests
+        }
+
+        /// The reason given for every path a validator turned down, oldest first.
+        pub fn rejections(&self) -> &[String] {
+            &self.rejections
+        }
+
+        /// How many answers are still waiting to be given.
+        pub fn answers_left(&self) -> usize {
+            self.answers.len()
+        }
+    }
+
+    impl DialogRunner for ScriptedRunner {
+        fn run(&mut self, request: &DialogRequest) -> Result<Option<DialogPick>, LibFileError> {
+            self.requests.push(request.clone());
+            let to_pick = |paths: Vec<String>, filterindex: Option<usize>| DialogPick {
+                paths: paths.into_iter().map(PathBuf::from).collect(),
+                filterindex,
+            };
+
+            match self.answers.pop_front() {
+                Some(ScriptedAnswer::Chosen(paths)) => Ok(Some(to_pick(paths, Some(request.filters.default_index())))),
+                Some(ScriptedAnswer::ChosenWithFilter(paths, index)) => Ok(Some(to_pick(paths, Some(index)))),
+                Some(ScriptedAnswer::ChosenUnknownFilter(paths)) => Ok(Some(to_pick(paths, None))),
+                Some(ScriptedAnswer::Cancel) => Ok(None),
+                Some(ScriptedAnswer::Error(err)) => Err(err),
+                None => Err(LibFileError::Dialog("The scripted runner has no answers left.".to_string())),
+            }
+        }
+
+        fn show_rejection(&mut self, _fullpath: &str, reason: &str) {
+            self.rejections.push(reason.to_string());
+        }
+    }
+
+    /// One entry of a `FilterSet`:  a label, such as `"Lists"`, and the
+    /// patterns it stands for, such as `["*.lst", "*.txt"]`.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FilterEntry {
+        pub label: String,
+        pub patterns: Vec<String>,
+    }
+
+    impl FilterEntry {
+        /// Returns `true` if this entry lets every file through (`*` or `*.*`).
+        pub fn is_all_files(&self) -> bool {
+            self.patterns.iter().any(|pattern| pattern == "*" || pattern == "*.*")
+        }
+
+        /// Returns the extension of the first plain `*.ext` pattern, without the `*.`.
+        /// Returns `None` for "All Files" entries and for patterns that hold
+        /// wildcards or braces after the dot.
+        pub fn default_extension(&self) -> Option<&str> {
+            if self.is_all_files() {
+                return None;
+            }
+            self.patterns.iter()
+                .filter_map(|pattern| pattern.strip_prefix("*."))
+                .find(|ext| !ext.is_empty() && !ext.contains(['*', '?', '{', '[']))
+        }
+
+        /// Returns `true` if the file name at the end of `path` matches one of
+        /// this entry's patterns.  Case is ignored, so `Algebra.LST` matches `*.lst`.
+        pub fn matches(&self, path: &str) -> bool {
+            let fname = match Path::new(path).file_name() {
+                Some(fname) => fname.to_string_lossy().to_lowercase(),
+                None => return false,
+            };
+            self.patterns.iter().any(|pattern| file_glob_match(&pattern.to_lowercase(), &fname))
+        }
+
+        /// Adds this entry's extension to `path` if the file name doesn't
+        /// already match the entry.  "All Files" entries, and entries with
+        /// no plain extension, leave the path as it is.
+        ///
+        /// # Example
+        ///
+        /// ```ignore
+        /// fn main() {
+        ///     let filters = FilterSet::new(&[("Variables", "*.vrbl")])
+        ///         .expect("The filters are written correctly.");
+        ///     let entry = &filters.entries()[0];
+        ///
+        ///     println!("{}", entry.add_extension("/home/user/banks/slope"));       // .../slope.vrbl
+        ///     println!("{}", entry.add_extension("/home/user/banks/slope.vrbl"));  // unchanged
+        /// }
+        /// ```
+        ///
+        pub fn add_extension(&self, path: &str) -> String {
+            if self.is_all_files() || self.matches(path) {
+                return path.to_string();
+            }
+            match self.default_extension() {
+                Some(extnsn) => format!("{}.{}", path, extnsn),
+                None => path.to_string(),
+            }
+        }
+
+        /// Renders the patterns the way FLTK expects them.  One pattern is used
+        /// as it is.  Several `*.ext` patterns become `*.{ext1,ext2}`, and any
+        /// other mix becomes `{pattern1,pattern2}`.
+        pub fn fltk_pattern(&self) -> String {
+            if self.patterns.len() == 1 {
+                return self.patterns[0].clone();
+            }
+
+            let extensions: Vec<&str> = self.patterns.iter()
+                .filter_map(|pattern| pattern.strip_prefix("*."))
+                .collect();
+            if extensions.len() == self.patterns.len() {
+                format!("*.{{{}}}", extensions.join(","))
+            } else {
+                format!("{{{}}}", self.patterns.join(","))
+            }
+        }
+    }
+
+    /// A checked set of file filters for the dialogs in this module.
+    ///
+    /// A `FilterSet` is built from pairs of labels and patterns.  A label can
+    /// stand for several patterns, separated by `;` (e.g. `"*.lst;*.txt"`).
+    /// A bare extension such as `"lst"` or `".lst"` is turned into `"*.lst"`.
+    /// The set can end with an "All Files" entry and can name the entry that
+    /// is selected when the dialog opens.
+    ///
+    /// Problems are caught when the set is built rather than when the dialog
+    /// opens.  Empty labels, empty patterns, a label used twice, tabs or
+    /// newlines (which would break FLTK's filter syntax), and an out-of-range
+    /// default index all return `LibFileError::InvalidFilter`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let filters = FilterSet::new(&[
+    ///             ("Lists", "*.lst;*.txt"),
+    ///             ("Variables", "vrbl"),
+    ///         ])
+    ///         .and_then(|set| set.with_all_files().with_default(1));
+    ///
+    ///     match filters {
+    ///         Ok(filters) => println!("{}", filters.fltk_filter()),
+    ///         Err(err) => println!("{}", err),
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Example output:
+    /// ```text
+    /// Lists       *.{lst,txt}
+    /// Variables   *.vrbl
+    /// All Files   *
+    /// ```
+    #[derive(Debug, Clone, PartialEq, Eq, Default)]
+    pub struct FilterSet {
+        entries: Vec<FilterEntry>,
+        defaultindex: usize,
+    }
+
+    impl FilterSet {
+        /// Builds a filter set from `(label, patterns)` pairs.  The first pair
+        /// is the default entry.  An empty slice gives an empty set, which
+        /// means "don't filter".
+        pub fn new(pairs: &[(&str, &str)]) -> Result<FilterSet, LibFileError> {
+            let mut entries = Vec::new();
+
+            for (label, patterns) in pairs {
+                let label = label.trim();
+                if label.is_empty() {
+                    return Err(LibFileError::InvalidFilter(format!("The patterns '{}' have no label.", patterns)));
+                }
+                if label.contains(['\t', '\n']) {
+                    return Err(LibFileError::InvalidFilter(format!("The label '{}' holds a tab or newline.", label)));
+                }
+                if entries.iter().any(|entry: &FilterEntry| entry.label == label) {
+                    return Err(LibFileError::InvalidFilter(format!("The label '{}' is used twice.", label)));
+                }
+
+                let mut usepatterns = Vec::new();
+                for pattern in patterns.split(';') {
+                    let pattern = pattern.trim();
+                    if pattern.is_empty() {
+                        return Err(LibFileError::InvalidFilter(format!("'{}' has an empty pattern.", label)));
+                    }
+                    if pattern.contains(['\t', '\n']) {
+                        return Err(LibFileError::InvalidFilter(format!("A pattern for '{}' holds a tab or newline.", label)));
+                    }
+                    usepatterns.push(fltk_normalize_pattern(pattern));
+                }
+
+                entries.push(FilterEntry { label: label.to_string(), patterns: usepatterns });
+            }
+
+            Ok(FilterSet { entries, defaultindex: 0 })
+        }
+
+        /// Adds an "All Files" entry to the end of the set, unless the set
+        /// already has one.
+        pub fn with_all_files(mut self) -> FilterSet {
+            if !self.entries.iter().any(|entry| entry.is_all_files()) {
+                self.entries.push(FilterEntry { label: "All Files".to_string(), patterns: vec!["*".to_string()] });
+            }
+            self
+        }
+
+        /// Chooses which entry (counting from 0) is selected when the dialog opens.
+        pub fn with_default(mut self, index: usize) -> Result<FilterSet, LibFileError> {
+            if index >= self.entries.len() {
+                return Err(LibFileError::InvalidFilter(
+                    format!("The default index {} is out of range for {} filters.", index, self.entries.len())));
+            }
+            self.defaultindex = index;
+            Ok(self)
+        }
+
+        /// The entries, in the order they appear in the dialog.
+        pub fn entries(&self) -> &[FilterEntry] {
+            &self.entries
+        }
+
+        /// The index of the entry selected when the dialog opens.
+        pub fn default_index(&self) -> usize {
+            self.defaultindex
+        }
+
+        /// The entry selected when the dialog opens, if the set isn't empty.
+        pub fn default_entry(&self) -> Option<&FilterEntry> {
+            self.entries.get(self.defaultindex)
+        }
+
+        /// Returns `true` if the set holds no entries.
+        pub fn is_empty(&self) -> bool {
+            self.entries.is_empty()
+        }
+
+        /// Renders the set in FLTK's filter syntax:  one `label<TAB>pattern`
+        /// line per entry.
+        pub fn fltk_filter(&self) -> String {
+            let parts: Vec<String> = self.entries.iter()
+                .map(|entry| format!("{}\t{}", entry.label, entry.fltk_pattern()))
+                .collect();
+            parts.join("\n")
+        }
+
+        /// Renders the set in the syntax of FLTK's own `dialog::FileChooser`:
+        /// `label (pattern)` entries separated by tabs.
+        pub fn fltk_chooser_filter(&self) -> String {
+            let parts: Vec<String> = self.entries.iter()
+                .map(|entry| format!("{} ({})", entry.label, entry.fltk_pattern()))
+                .collect();
+            parts.join("\t")
+        }
+    }
+
+    /// Turns a bare extension (`lst` or `.lst`) into a pattern (`*.lst`).
+    /// Anything that already holds a wildcard or braces is left alone.
+    fn fltk_normalize_pattern(pattern: &str) -> String {
+        if pattern.contains(['*', '?', '{', '[']) {
+            pattern.to_string()
+        } else {
+            format!("*.{}", pattern.trim_start_matches('.'))
+        }
+    }
+
+    /// Prompts the user with a file-save dialog, allowing them to select a file location and name.
+    ///
+    /// # Parameters
+    ///
+    /// - `sggstdpath`: A suggested directory path where the dialog should initially open.
+    ///   This value is used as a starting point for the file browser, but users can change
+    ///   the location if desired. If the directory is invalid or does not exist, the
+    ///   function defaults to the user's home directory.
+    /// - `sggstdname`: A suggested filename to prefill in the dialog box. Users can
+    ///   change this as necessary.
+    /// - `filters`: A `FilterSet` holding the selectable filters for the file-saving
+    ///   process.  The extension of its default entry is used as the default file extension.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(path))` holding the full path selected by the user, including the chosen
+    ///   file name.
+    /// - `Ok(None)` if the user cancelled the dialog.
+    /// - `Err(LibFileError)` if the dialog could not be set up or shown.
+    ///
+    /// # Function Workflow
+    ///
+    /// 1. **Validate Input Directory Path**:
+    ///    - Confirm that `sggstdpath` points to a valid directory.
+    ///    - When invalid, the function defaults to the home directory.
+    /// 2. **Initialize the File Browser**:
+    ///    - A `NativeFileChooser` dialog box of type `BrowseSaveFile` is created and set to open
+    ///      at the validated starting directory.
+    /// 3. **Apply Default File Extension**:
+    ///    - If the default entry of `filters` has an extension and `sggstdname` has no
+    ///      extension yet, the default extension is appended to `sggstdname`.
+    /// 4. **Set File Filters**:
+    ///    - Render `filters` in FLTK's filter syntax and apply it to the file browser.
+    /// 5. **User Interaction**:
+    ///    - Display the dialog to the user, allowing them to choose a file path and name.
+    /// 6. **Enforce the Picked Extension**:
+    ///    - If the typed name doesn't match the filter the user picked, that filter's
+    ///      extension is added to it.  Nothing is added for "All Files".
+    /// 7. **Return the Path**:
+    ///    - The user-selected file path is returned as `Ok(Some(String))`.  If adding the
+    ///      extension turned it into a file that already exists, the user is asked first.
+    ///    - To be asked about every existing file, build the dialog with
+    ///      `DialogBuilder::confirm_overwrite(true)` instead.
+    ///
+    /// # Assumptions
+    ///
+    /// - The user's environment supports a native file dialog (e.g., a GUI-based desktop).
+    ///
+    /// # Example
+    ///
+    ///     fn main() {
+    ///         let app = app::App::default();
+    ///         let suggested_path = "/home/user/Documents";
+    ///         let suggested_name = "new_file";
+    ///         let filters = FilterSet::new(&[("Text Files", "*.txt;*.csv")])
+    ///             .expect("The filters are written correctly.");
+    ///
+    ///         match file_browse_tosave(suggested_path, suggested_name, &filters) {
+    ///             Ok(Some(path)) => println!("Selected file path: {}", path),
+    ///             Ok(None) => println!("The user cancelled."),
+    ///             Err(err) => println!("The dialog failed: {}", err),
+    ///         }
+    ///     }
+    ///
+    /// # Notes
+    ///
+    /// - The user can override both the suggested directory and file name.
+    /// - Invoking the dialog may depend on system-dependent GUI frameworks.
+    ///
+    /// # Errors
+    ///
+    /// - Returns an error if the `set_directory` operation fails for the given directory path.
+    /// - If `filters` is empty or its default entry is "All Files", no extension is added to the name.
+    ///
+    /// # See Also:
+    ///
+    /// - `DialogBuilder`, which this function is a thin wrapper around.
+    /// - `dialog::NativeFileChooser` for more details about the underlying dialog implementation.
+    /// - `fltk_build_dialogfltr` for details on constructing the filter string.
+    pub fn file_browse_tosave(sggstdpath: &str, sggstdname: &str, filters: &FilterSet)
+                                                    -> Result<Option<String>, LibFileError> {
+        file_browse_tosave_parent(sggstdpath, sggstdname, filters, None)
+    }
+
+    /// Same as `file_browse_tosave` except that the dialog is tied to the passed
+    /// `parent` window and opens on top of it, modal to it (see
+    /// `DialogBuilder::parent()`).  Passing `None` gives the same behavior as
+    /// `file_browse_tosave`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let app = app::App::default();
+    ///     let mut win = window::Window::new(100, 100, 400, 300, "Editor");
+    ///     win.end();
+    ///     win.show();
+    ///
+    ///     let filters = FilterSet::new(&[("Lists", "*.lst")])
+    ///         .expect("The filters are written correctly.")
+    ///         .with_all_files();
+    ///     let result = file_browse_tosave_parent("/home/user/Documents", "new_list",
+    ///                                            &filters, Some(&win));
+    ///     match result {
+    ///         Ok(Some(path)) => println!("Selected file path: {}", path),
+    ///         Ok(None) => println!("The user cancelled."),
+    ///         Err(err) => println!("The dialog failed: {}", err),
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn file_browse_tosave_parent(sggstdpath: &str, sggstdname: &str, filters: &FilterSet,
+                                     parent: Option<&Window>) -> Result<Option<String>, LibFileError> {
+
+// region Note:
+//   The passed string `sggstdpath` should be a suggested directory for saving a
+//   file using the suggested `sggstdname` for a file name.  Note that both
+//   variables are "suggestions" allowing for the user to change either.
+// endregion
+
+        DialogBuilder::new(DialogMode::Save)
+            .start_dir(sggstdpath)
+            .preset_name(sggstdname)
+            .filters(filters)
+            .parent(parent)
+            .show()
+    }
+
+    /*
+    /// Opens a native file save dialog for the user to select a location
+    /// and name for saving a file, with pre-suggested parameters and a file extension filter.
+    ///
+    /// # Parameters
+    /// - `sggstdpath`: A reference to a string that specifies the suggested directory to open in the file dialog.
+    ///                 If the directory does not exist, it defaults to the user's home directory.
+    /// - `sggstdname`: A reference to a string that specifies the suggested default file name.
+    /// - `sggstdextnsn`: A reference to a string that defines the file extension or filter type for the save dialog.
+    ///                   This may include the `*.` prefix (e.g., `*.txt`) but is not required.
+    /// - `wintitle`: A reference to a string that specifies the title of the file save dialog window.
+    ///
+    /// # Returns
+    /// Returns a `String` that represents the full path of the file chosen or saved by the user.
+    ///
+    /// # Behavior
+    /// - Ensures the suggested directory (`sggstdpath`) exists before launching the file dialog.
+    /// - Uses the suggested directory and file name as defaults in the save dialog but allows the user to modify them.
+    /// - Automatically appends the provided file extension (`sggstdextnsn`) to the suggested file name if it is not already included.
+    /// - Sets a filter in the file dialog to display only files matching the provided extension and also adds an
+    ///   "All Files" filter option for displaying all files.
+    /// - Displays the dialog with the title provided in `wintitle`.  That title can also serve as a
+    ///     prompt to help guide the user.
+    ///
+    /// # Notes
+    /// - The `sggstdpath` and `sggstdname` parameters are suggestions only; the user can change both during the browsing process.
+    /// - If an invalid directory is specified in `sggstdpath`, it defaults to the user's home directory.
+    ///
+    /// # Example
+    ///     fn main() {
+    ///         let usedir = "/home/jtreagan/programming/mine/qbnk_rb7/src/qbnk_data/lists";
+    ///         let path = file_browse_tosave(usedir, "fakefilename", "txt");
+    ///         println!("\n {} \n", path);
+    ///     }
+    ///
+    /// # Dependencies
+    /// - This function utilizes `dialog::NativeFileChooser` for handling the native file save dialog.
+    /// - The function requires utilities like `dir_check_valid` to validate the suggested directory.
+    pub fn file_browse_tosave_old(sggstdpath: &str, sggstdname: &str, sggstdextnsn: &str) -> String {
+
+        // region Note:
+        //          The passed string `sggstdpath` should be a suggested directory for saving a
+        //          file using the suggested `sggstdname` for a file name.  Note that both
+        //          variables are "suggestions" allowing for the user to change either.
+        // endregion
+        
+        // region Check that the passed directory exists and `startpath` is ready.
+        let track = dir_check_valid(&mut sggstdpath.to_string());  // Defaults to home directory on err.
+        let startpath = Path::new(track.as_str());
+        // endregion
+
+        println!("FBW1");
+
+        // region Call a dialog browser and set it to the passed directory.
+        let mut fchooser = dialog::NativeFileChooser
+                                ::new(dialog::NativeFileChooserType
+                                ::BrowseSaveFile);
+        fchooser.set_directory(&startpath).expect("Cannot set directory.");
+        // endregion
+
+        println!("FBW2");
+
+        // region  Add the passed extension to the suggested file name.
+        let usename;
+        if sggstdextnsn != "" {
+            let ext_to_append = sggstdextnsn.strip_prefix("*.").unwrap_or(sggstdextnsn);
+            usename = format!("{}.{}", sggstdname, ext_to_append);
+        }  else {
+            usename = sggstdname.to_string();
+        }
+        fchooser.set_preset_file(usename.as_str());
+        // endregion
+
+        println!("FBW3");
+
+        // region Create the filter string & set the filter.
+
+        // todo: Pass the extension suggestion in a vector that can include multiple extensions.
+        //      Include an extension title in that vector.  Would a hashmap be a better choice?
+
+        let combined_filter;
+        if sggstdextnsn == "" {
+            combined_filter = "All Files\t*.*".to_string();
+
+
+        }  else {
+            let useext;
+            if !sggstdextnsn.starts_with("*.") {
+                useext = format!("*.{}", sggstdextnsn);
+            } else {
+                useext = sggstdextnsn.to_string();
+            }
+            combined_filter = format!("Type   \t{}\nAll Files   \t*.*", useext);
+        }
 
-    /// Prompts the user with a file-save dialog, allowing them to select a file location and name.
+        fchooser.set_filter(&combined_filter);
+        // endregion
+
+        println!("FBW4");
+
+        fchooser.show();
+
+        println!("FBW5");
+
+        let path = fchooser.filename().to_str().unwrap().to_string();
+
+        println!("FBW6");
+
+        path
+    }
+    */  // Deleteme later.
+
+    /// Opens a native directory chooser dialog, sets its starting path and window title,
+    /// and returns the selected directory path as a `String`.
     ///
-    /// # Parameters
+    /// # Arguments
     ///
-    /// - `sggstdpath`: A suggested directory path where the dialog should initially open.
-    ///   This value is used as a starting point for the file browser, but users can change
-    ///   the location if desired. If the directory is invalid or does not exist, the
-    ///   function defaults to the user's home directory.
-    /// - `sggstdname`: A suggested filename to prefill in the dialog box. Users can
-    ///   change this as necessary.
-    /// - `extnsnvec`: A vector of file extensions to use as selectable filters during the file-saving
-    ///   process. The first item in the vector is assumed to be a label, and the second item is used
-    ///   to define the default file extension.
+    /// * `sggstdpath` - A mutable string slice representing the initial directory path.
+    ///   If the provided path is invalid, it defaults to the user's home directory.
+    /// * `wintitle` - A string slice representing the title of the dialog window.
     ///
     /// # Returns
     ///
-    /// - Returns a `String` representing the full path selected by the user, including the chosen
-    ///   file name. If the dialog is cancelled, this value may vary depending on implementation and
-    ///   should be treated with caution.
+    /// * `Ok(Some(path))` holding the selected directory path.
+    /// * `Ok(None)` if the user cancelled the dialog.
     ///
-    /// # Function Workflow
+    /// # Errors
     ///
-    /// 1. **Validate Input Directory Path**:
-    ///    - Confirm that `sggstdpath` points to a valid directory.
-    ///    - When invalid, the function defaults to the home directory.
-    /// 2. **Initialize the File Browser**:
-    ///    - A `NativeFileChooser` dialog box of type `BrowseSaveFile` is created and set to open
-    ///      at the validated starting directory.
-    /// 3. **Apply Default File Extension**:
-    ///    - If `extnsnvec` has a valid second entry (default extension), it is appended to
-    ///      `sggstdname` as the default suggested filename.
-    /// 4. **Set File Filters**:
-    ///    - Create a combined filter string from `extnsnvec` and apply it to the file browser.
-    /// 5. **User Interaction**:
-    ///    - Display the dialog to the user, allowing them to choose a file path and name.
-    ///    - The user-selected file path is then returned as a `String`.
+    /// Returns an error if the directory passed to `fchooser.set_directory()`
+    /// cannot be set or the dialog itself fails.
     ///
-    /// # Assumptions
+    /// # Examples
     ///
-    /// - `extnsnvec` contains at least two elements and should contain an even number of elements.
-    ///   The first entry is a label and
-    ///   the second entry specifies the default file extension.
-    /// - The user's environment supports a native file dialog (e.g., a GUI-based desktop).
-    /// - The second element in `extnsnvec`, if present, will follow the typical pattern of
-    ///   file extension filters (e.g., `"*.txt"`).
+    ///     fn main() {
+    ///         let usedir = "/home/jtreagan/programming/mine/qbnk_rb7/src/qbnk_data/lists";
+    ///         match file_pathonly(usedir, "TITLE TITLE TITLE") {
+    ///             Ok(Some(path)) => println!("\n {} \n", path),
+    ///             Ok(None) => println!("\n No directory was chosen. \n"),
+    ///             Err(err) => println!("\n Error:  {} \n", err),
+    ///         }
+    ///     }
     ///
-    /// # Example
+    /// # Notes
+    ///
+    /// This function relies on `dialog::NativeFileChooser` for platform-native file chooser dialogs
+    /// and uses the helper function `dir_resolve_or_home` to handle and sanitize the input directory path.
+    pub fn file_pathonly(sggstdpath: &str, wintitle: &str) -> Result<Option<String>, LibFileError> {
+        file_pathonly_parent(sggstdpath, wintitle, None)
+    }
+
+    /// Same as `file_pathonly` except that the dialog is tied to the passed
+    /// `parent` window and opens on top of it, modal to it (see
+    /// `DialogBuilder::parent()`).  Passing `None` gives the same behavior as
+    /// `file_pathonly`.
+    pub fn file_pathonly_parent(sggstdpath: &str, wintitle: &str, parent: Option<&Window>)
+                                                    -> Result<Option<String>, LibFileError> {
+        DialogBuilder::new(DialogMode::Dir)
+            .start_dir(sggstdpath)
+            .title(wintitle)
+            .parent(parent)
+            .show()
+    }
+
+    /// A function that presents a native file chooser dialog to the user, allowing them to browse
+    /// for a file starting from a specified directory, and returns the selected file's name only.
+    ///
+    /// This function takes two inputs: a mutable reference to a string representing the starting directory
+    /// and a string for the window's title. It normalizes the directory path, initializes the native
+    /// file chooser dialog, sets its initial directory to the given path, and allows the user to select a file.
+    /// The name of the selected file (with no accompanying path information) is then extracted and returned as a `String`.
+    ///
+    /// # Arguments
+    /// * `sggstdpath` - A mutable reference to a string slice representing the starting
+    ///   path. If invalid or unavailable, it defaults to the user's home directory.
+    /// * `wintitle` - The title of the window for the file chooser dialog.
+    ///
+    /// # Returns
+    /// * `Ok(Some(name))` holding the name of the selected file, which is extracted from the
+    ///   full path provided by the file chooser.
+    /// * `Ok(None)` if the user cancelled the dialog.
+    ///
+    /// # Errors
+    /// This function returns an error in the following scenarios:
+    /// 1. If the set directory is invalid or cannot be set (this is handled through the dialog API).
+    /// 2. If the returned file path does not contain a valid file name.
+    /// 3. If the extracted filename is not valid UTF-8.
     ///
+    /// # Example
     ///     fn main() {
-    ///         let app = app::App::default();
-    ///         let suggested_path = "/home/user/Documents";
-    ///         let suggested_name = "new_file";
-    ///         let extensions = vec!["Text Files", "*.txt", "*.csv"];
+    ///         let usedir = "/some/existing/directory";
+    ///         match file_nameonly(usedir, "TITLE TITLE TITLE") {
+    ///             Ok(Some(name)) => println!("\n {} \n", name),
+    ///             Ok(None) => println!("\n No file was chosen. \n"),
+    ///             Err(err) => println!("\n Error:  {} \n", err),
+    ///         }
+    ///     }
+    ///
+    /// Note: This function uses `dialog::NativeFileChooser` for presenting the file dialog
+    /// and `std::path::Path` for dealing with directory paths.
+    pub fn file_nameonly(sggstdpath: &str, wintitle: &str) -> Result<Option<String>, LibFileError> {
+        file_nameonly_parent(sggstdpath, wintitle, None)
+    }
+
+    /// Same as `file_nameonly` except that the dialog is tied to the passed
+    /// `parent` window and opens on top of it, modal to it (see
+    /// `DialogBuilder::parent()`).  Passing `None` gives the same behavior as
+    /// `file_nameonly`.
+    pub fn file_nameonly_parent(sggstdpath: &str, wintitle: &str, parent: Option<&Window>)
+                                                    -> Result<Option<String>, LibFileError> {
+        DialogBuilder::new(DialogMode::Open)
+            .start_dir(sggstdpath)
+            .title(wintitle)
+            .parent(parent)
+            .shape(ReturnShape::NameOnly)
+            .show()
+    }
+
+    /// Generates a dialog to choose a file, starting from a specified directory,
+    /// and returns the full path of the selected file.
+    ///
+    /// # Arguments
+    /// - `sggstdpath`: A mutable reference to a string slice representing the starting directory path.
+    ///   If the path is invalid or does not exist, it defaults to the home directory.
+    /// - `wintitle`: A string slice that sets the window title of the file chooser dialog.
+    ///
+    /// # Returns
+    /// - `Ok(Some(path))` containing the full path of the selected file.
+    /// - `Ok(None)` if the user cancelled the dialog.
+    ///
+    /// # Behavior
+    /// 1. Validates and normalizes the given directory path (`sggstdpath`) to ensure it exists.
+    ///    If validation fails, defaults the starting path to the home directory.
+    /// 2. Displays a file chooser dialog starting from the provided (or adjusted) directory
+    ///    and assigns the given `wintitle` as the window's title.
+    /// 3. Retrieves the full path of the selected file and returns it as a string.
+    ///
+    /// # Errors
+    /// - If there is a problem setting the directory for the file chooser dialog, or the
+    ///   dialog itself fails, the error is passed back to the caller.
     ///
-    ///         let result = file_browse_tosave(suggested_path, suggested_name, &extensions);
-    ///         println!("Selected file path: {}", result);
+    /// # Example
+    ///     fn main() {
+    ///         let usedir = "/home/jtreagan/programming/mine/qbnk_rb7/src/qbnk_data/lists";
+    ///         match file_fullpath(usedir, "TITLE TITLE TITLE") {
+    ///             Ok(Some(path)) => println!("\n {} \n", path),
+    ///             Ok(None) => println!("\n No file was chosen. \n"),
+    ///             Err(err) => println!("\n Error:  {} \n", err),
+    ///         }
     ///     }
     ///
     /// # Notes
+    /// - The function uses the `dialog` crate to create a native file chooser dialog.
+    /// - The provided `sggstdpath` is validated and normalized using `dir_resolve_or_home`.
+    /// - The resulting file path is converted to a `String` and returned.
+    pub fn file_fullpath(sggstdpath: &str, wintitle: &str) -> Result<Option<String>, LibFileError> {
+        file_fullpath_parent(sggstdpath, wintitle, None)
+    }
+
+    /// Same as `file_fullpath` except that the dialog is tied to the passed
+    /// `parent` window and opens on top of it, modal to it (see
+    /// `DialogBuilder::parent()`).  Passing `None` gives the same behavior as
+    /// `file_fullpath`.
+    pub fn file_fullpath_parent(sggstdpath: &str, wintitle: &str, parent: Option<&Window>)
+                                                    -> Result<Option<String>, LibFileError> {
+        DialogBuilder::new(DialogMode::Open)
+            .start_dir(sggstdpath)
+            .title(wintitle)
+            .parent(parent)
+            .show()
+    }
+
+
+    /// Opens a dialog in which the user can pick several files at once, and
+    /// returns the full path of each one.
     ///
-    /// - The user can override both the suggested directory and file name.
-    /// - Invoking the dialog may depend on system-dependent GUI frameworks.
-    /// - Returns an empty string or unexpected value if the dialog is cancelled, so handle
-    ///   that return value carefully.
+    /// # Arguments
+    /// - `sggstdpath`: The directory the dialog starts in.  A path to a file starts
+    ///   in that file's directory, and a path that cannot be resolved starts in the
+    ///   home directory, the same as `file_fullpath`.
+    /// - `wintitle`: The title of the dialog window.
+    /// - `filters`: The filters the user can choose between.  Pass an empty
+    ///   `FilterSet` (`FilterSet::default()`) to show every file.
+    ///
+    /// # Returns
+    /// - `Ok(Some(paths))` holding the full paths of the chosen files.  The vector is never empty.
+    /// - `Ok(None)` if the user cancelled the dialog.
     ///
     /// # Errors
+    /// - Returns an error if the dialog cannot be set up or shown, or if a chosen path
+    ///   is not valid UTF-8.
     ///
-    /// - Panics if the `set_directory` operation fails for the given directory path.
-    /// - If `extnsnvec[1]` is absent or invalid, using an invalid index will cause a panic.
+    /// # Example
+    /// ```ignore
+    /// fn main() {
+    ///     let app = app::App::default();
+    ///     let filters = FilterSet::new(&[("Lists", "*.lst"), ("Variables", "*.vrbl")])
+    ///         .expect("The filters are written correctly.");
+    ///
+    ///     match file_fullpath_multi("/home/user/qbnk_data", "Import into the bank", &filters) {
+    ///         Ok(Some(paths)) => {
+    ///             for path in paths {
+    ///                 println!("Importing {}", path);
+    ///             }
+    ///         }
+    ///         Ok(None) => println!("Nothing was imported."),
+    ///         Err(err) => println!("The dialog failed: {}", err),
+    ///     }
+    /// }
+    /// ```
     ///
-    /// # See Also:
+    pub fn file_fullpath_multi(sggstdpath: &str, wintitle: &str, filters: &FilterSet)
+                                                    -> Result<Option<Vec<String>>, LibFileError> {
+        file_fullpath_multi_parent(sggstdpath, wintitle, filters, None)
+    }
+
+    /// Same as `file_fullpath_multi` except that the dialog is tied to the passed
+    /// `parent` window and opens on top of it, modal to it (see
+    /// `DialogBuilder::parent()`).  Passing `None` gives the same behavior as
+    /// `file_fullpath_multi`.
+    pub fn file_fullpath_multi_parent(sggstdpath: &str, wintitle: &str, filters: &FilterSet,
+                                      parent: Option<&Window>) -> Result<Option<Vec<String>>, LibFileError> {
+        DialogBuilder::new(DialogMode::Multi)
+            .start_dir(sggstdpath)
+            .title(wintitle)
+            .filters(filters)
+            .parent(parent)
+            .show_multi()
+    }
+
+    // note: The following functions are now thin wrappers around `DialogBuilder`
+    //          and are kept for the programs that already use them.
+    
+    /// Browse to a desired directory, filter the files by the passed extension.
+    /// The returned string includes both the path and the file name.
+    /// Returns `Ok(None)` if the user cancels the dialog.
     ///
-    /// - `dialog::NativeFileChooser` for more details about the underlying dialog implementation.
-    /// - `fltk_build_dialogfltr` for details on constructing the filter string.
+    /// Note that the `extension` value must have format  `*.xxxxx`.  An empty
+    /// `extension` shows every file.
+    /// Note that a file must be highlighted before the dialog will close.
+    pub fn file_fullpath_fltr(usedir: &String, extension: &str) -> Result<Option<String>, LibFileError> {
+        file_fullpath_fltr_parent(usedir, extension, None)
+    }
+
+    /// Same as `file_fullpath_fltr` except that the dialog is tied to the passed
+    /// `parent` window and opens on top of it, modal to it (see
+    /// `DialogBuilder::parent()`).  Passing `None` gives the same behavior as
+    /// `file_fullpath_fltr`.
+    pub fn file_fullpath_fltr_parent(usedir: &String, extension: &str, parent: Option<&Window>)
+                                                    -> Result<Option<String>, LibFileError> {
+        // Note that the `extension` value must have format  `*.xxxxx`.
+        DialogBuilder::new(DialogMode::Open)
+            .start_dir(usedir)
+            .filters(&fltk_extension_filter(extension)?)
+            .parent(parent)
+            .show()
+    }
+
+    /// Browse to a desired directory, filter the files by the passed extension.
+    /// The returned string includes only the file name.
+    /// Returns `Ok(None)` if the user cancels the dialog.
+    pub fn file_nameonly_fltr(usedir: &String, extension: &str) -> Result<Option<String>, LibFileError> {
+        file_nameonly_fltr_parent(usedir, extension, None)
+    }
+
+    /// Same as `file_nameonly_fltr` except that the dialog is tied to the passed
+    /// `parent` window and opens on top of it, modal to it (see
+    /// `DialogBuilder::parent()`).  Passing `None` gives the same behavior as
+    /// `file_nameonly_fltr`.
+    pub fn file_nameonly_fltr_parent(usedir: &String, extension: &str, parent: Option<&Window>)
+                                                    -> Result<Option<String>, LibFileError> {
+        // Note that the `extension` value must have format  `*.xxxxx`.
+        DialogBuilder::new(DialogMode::Open)
+            .start_dir(usedir)
+            .filters(&fltk_extension_filter(extension)?)
+            .parent(parent)
+            .shape(ReturnShape::NameOnly)
+            .show()
+    }
+
+    /// The filter for the `_fltr` functions above, with the extension as its
+    /// own label.  An empty extension gives an empty set, which shows every
+    /// file, as it always has.
+    fn fltk_extension_filter(extension: &str) -> Result<FilterSet, LibFileError> {
+        match extension.trim().is_empty() {
+            true => Ok(FilterSet::default()),
+            false => FilterSet::new(&[(extension, extension)]),
+        }
+    }
+
+    /// Constructs a filter string for use in FLTK dialog functions from a `FilterSet`.
+    ///
+    /// # Parameters
+    ///
+    /// * `filters`: A `FilterSet` holding the labels and the patterns that go with them.
+    ///   The set was checked when it was built, so every entry here is usable.
+    ///
+    /// # Returns
+    ///
+    /// Returns a formatted string with the label and extension/filter pattern pairs, each pair on a new line. The
+    /// format for each line is:
+    ///
+    /// ```text
+    /// label<TAB>extension_pattern
+    /// ```
+    ///
+    /// # Example
+    ///
+    ///     fn main() {
+    ///         let filters = FilterSet::new(&[
+    ///                 ("Text Files", "*.txt"),
+    ///                 ("Images", "*.jpg;*.png"),
+    ///                 ("Rust Files", "rs"),
+    ///             ])
+    ///             .expect("The filters are written correctly.");
+    ///
+    ///         let dialog_filter = fltk_build_dialogfltr(&filters);
+    ///         println!("{}", dialog_filter);
+    ///     }
+    ///
+    /// Example output:
+    /// ```text
+    /// Text Files	*.txt
+    /// Images	*.{jpg,png}
+    /// Rust Files	*.rs
+    /// ```
+    ///
+    /// # Notes
     ///
-    /// # Region Breakdown
+    /// - Bare extensions such as `rs` have `*.` put in front of them when the `FilterSet` is built.
+    /// - Labels with several patterns are joined into one FLTK pattern.  See `FilterEntry::fltk_pattern`.
     ///
-    /// - The function is broken into regions for validating paths, setting up dialog filters,
-    ///   processing suggested filenames, and showing the file browser to the user.
-    pub fn file_browse_tosave(sggstdpath: &str, sggstdname: &str, extnsnvec: &Vec<&str>) -> String {
+    pub fn fltk_build_dialogfltr(filters: &FilterSet) -> String {
+        filters.fltk_filter()
+    }
 
-// region Note:
-//   The passed string `sggstdpath` should be a suggested directory for saving a
-//   file using the suggested `sggstdname` for a file name.  Note that both
-//   variables are "suggestions" allowing for the user to change either.
-// endregion
+    /// Shows a native file chooser and reports what the user did.  If a `parent`
+    /// window is passed, the chooser is opened over it as far as the platform allows.
+    ///
+    /// The native chooser belongs to the platform, not to FLTK, so it can't be
+    /// made modal or transient for a particular FLTK window.  Where the
+    /// platform attaches the chooser to an owner at all, FLTK hands it its
+    /// first window, which is not necessarily the window the user is working
+    /// in.  Raising the parent (and flushing) just before the chooser opens
+    /// makes the parent that first window.  Choosers that run as a separate
+    /// program (zenity or kdialog on some Linux desktops) have no owner, and
+    /// the window manager places them as it likes.  Once the chooser closes,
+    /// the parent is raised again so it gets the focus back.
+    ///
+    /// Returns `Ok(Some(paths))` for the chosen path(s), `Ok(None)` if the user
+    /// cancelled, and `Err` if the chooser itself failed.
+    fn fltk_show_on_parent(fchooser: &mut dialog::NativeFileChooser, parent: Option<&Window>)
+                                                    -> Result<Option<Vec<PathBuf>>, LibFileError> {
+        let action = match parent {
+            Some(win) => {
+                let mut usewin = win.clone();
+                usewin.show();
+                app::flush();
+                let action = fchooser.try_show();
+                usewin.show();
+                action
+            }
+            None => fchooser.try_show(),
+        };
+
+        match action? {
+            dialog::FileDialogAction::Cancelled => Ok(None),
+            dialog::FileDialogAction::Success => {
+                let chosen = fchooser.filenames();
+                if chosen.is_empty() {  // Some backends report success with nothing chosen.
+                    Ok(None)
+                } else {
+                    Ok(Some(chosen))
+                }
+            }
+        }
+    }
 
-        // region Check that the passed directory exists and `startpath` is ready.
-        let track = dir_check_valid(&mut sggstdpath.to_string());  // Defaults to home directory on err.
-        let startpath = Path::new(track.as_str());
-        // endregion
+    /// Makes FLTK's own chooser modal and, if a `parent` window is passed,
+    /// centres it over that window and ties it to it.
+    ///
+    /// FLTK has no call to name the owner of a window.  A modal window is
+    /// made transient for FLTK's first window when it is shown, so the
+    /// parent is raised (and flushed) first to make it that window.  The
+    /// window manager then keeps the chooser above the parent, and the
+    /// parent and every other FLTK window ignore input until it closes.
+    fn fltk_chooser_on_parent(fchooser: &mut dialog::FileChooser, parent: Option<&Window>) {
+        fchooser.window().make_modal(true);
+
+        let mut usewin = match parent {
+            Some(win) => win.clone(),
+            None => return,
+        };
+        usewin.show();
+        app::flush();
+
+        let xpos = usewin.x() + (usewin.w() - fchooser.w()) / 2;
+        let ypos = usewin.y() + (usewin.h() - fchooser.h()) / 2;
+        fchooser.set_position(xpos.max(0), ypos.max(0));
+    }
 
-        // region Call a dialog browser and set it to the passed directory.
-        let mut fchooser = dialog::NativeFileChooser
-        ::new(dialog::NativeFileChooserType
-        ::BrowseSaveFile);
-        fchooser.set_directory(&startpath).expect("Cannot set directory.");
+    /// Sets up FLTK's own chooser for `request` and shows it over the
+    /// parent window, without waiting for it to close.
+    fn fltk_open_chooser(request: &DialogRequest) -> dialog::FileChooser {
+        let startpath = Path::new(&request.startdir);
+
+        // region Set up the dialog browser.
+        let choosertype = match request.mode {
+            DialogMode::Open => dialog::FileChooserType::Single,
+            DialogMode::Save => dialog::FileChooserType::Create,
+            DialogMode::Dir => dialog::FileChooserType::Directory,
+            DialogMode::Multi => dialog::FileChooserType::Multi,
+        };
+        let wintitle = match (request.title.is_empty(), request.mode) {
+            (false, _) => request.title.as_str(),
+            (true, DialogMode::Save) => "Save As",
+            (true, DialogMode::Dir) => "Choose a Folder",
+            (true, _) => "Open",
+        };
+        let mut fchooser = dialog::FileChooser::new(startpath, &request.filters.fltk_chooser_filter(),
+                                                    choosertype, wintitle);
+
+        if !request.filters.is_empty() {
+            fchooser.set_filter_value(request.filters.default_index() as i32);
+        }
+        if !request.presetname.is_empty() {
+            fchooser.set_value(&startpath.join(&request.presetname).to_string_lossy());
+        }
+        if request.mode == DialogMode::Save {
+            fchooser.set_ok_label("Save");
+        }
+        fchooser.set_preview(request.preview);
         // endregion
 
-        // region  Add the passed extension to the suggested file name.
 
-        let usename;
+        fltk_chooser_on_parent(&mut fchooser, request.parent.as_ref());
+        fchooser.show();
+        fchooser
+    }
 
-        let defaultextnsn = extnsnvec[1];  // Assumes the first entry is a label so chooses the second.
-        if defaultextnsn != "" {
-            let ext_to_append = defaultextnsn.strip_prefix("*.").unwrap_or(defaultextnsn);
-            usename = format!("{}.{}", sggstdname, ext_to_append);
-        } else {
-            usename = sggstdname.to_string();
+    /// What the user picked in FLTK's own chooser, once it has closed.
+    /// If the user picks one of FLTK's own filters ("All Files" or
+    /// "Custom Filter"), no filter index is reported.
+    fn fltk_chooser_pick(fchooser: &mut dialog::FileChooser, request: &DialogRequest) -> Option<DialogPick> {
+        let mut chosen = Vec::new();
+        for index in 1..=fchooser.count() {
+            chosen.extend(fchooser.value(index).map(PathBuf::from));  // `None` means the user cancelled.
+        }
+        let filterindex = match usize::try_from(fchooser.filter_value()) {
+            Ok(index) if index < request.filters.entries().len() => Some(index),
+            _ => None,
+        };
+
+        match chosen.is_empty() {
+            true => None,
+            false => Some(DialogPick { paths: chosen, filterindex }),
         }
+    }
 
-        fchooser.set_preset_file(usename.as_str());
+    /// The remembered directory for `purpose`, if a store is installed and
+    /// the directory still exists.
+    fn fltk_remembered_dir(purpose: &str) -> Option<String> {
+        let remembered = LAST_DIRS.with(|current| match current.borrow().as_ref() {
+            Some(lastdirs) => lastdirs.get(purpose).map(|dir| dir.to_string()),
+            None => None,
+        });
+        remembered.filter(|dir| Path::new(dir).is_dir())
+    }
 
-        // endregion
+    /// Remembers the directory of `fullpath` (or `fullpath` itself in
+    /// `DialogMode::Dir`) for `purpose`, and saves the store.  Nothing
+    /// happens if no store is installed.
+    fn fltk_remember_dir(purpose: &str, mode: DialogMode, fullpath: &str) {
+        let chosendir = match mode {
+            DialogMode::Dir => Some(Path::new(fullpath)),
+            _ => Path::new(fullpath).parent(),
+        };
+        let chosendir = match chosendir {
+            Some(dir) => dir.to_string_lossy().into_owned(),
+            None => return,
+        };
+
+        LAST_DIRS.with(|current| {
+            for lastdirs in current.borrow_mut().iter_mut() {  // Runs once if a store is installed.
+                // A failed set or save only costs the memory of this one choice.
+                let _ = lastdirs.set(purpose, &chosendir).and_then(|_| lastdirs.save());
+            }
+        });
+    }
 
-        // region Create the filter string & set the filter.
+    /// Shows FLTK's own chooser for `builder` without waiting, and checks
+    /// from a repeating timeout whether it has closed.  Once it has, the
+    /// pick is settled as `show_multi()` would, and either the chooser is
+    /// shown again or `on_done` gets the result.
+    fn fltk_watch_chooser<F>(builder: DialogBuilder, purpose: String, startdir: String, presetname: String,
+                             on_done: F)
+    where
+        F: FnOnce(Result<Option<Vec<String>>, LibFileError>) + 'static,
+    {
+        const POLL_SECS: f64 = 0.05;
+
+        let request = builder.dialog_request(&startdir, &presetname);
+        let mut fchooser = fltk_open_chooser(&request);
+        let mut waiting = Some((builder, purpose, startdir, presetname, on_done));
+        app::add_timeout3(POLL_SECS, move |handle| {
+            if fchooser.shown() {
+                app::repeat_timeout3(POLL_SECS, handle);
+                return;
+            }
+            let (builder, purpose, mut startdir, mut presetname, on_done) = match waiting.take() {
+                Some(waiting) => waiting,
+                None => return,
+            };
+
+            let pick = fltk_chooser_pick(&mut fchooser, &request);
+            match builder.settle(&DialogBackend::Fltk, pick, &mut startdir, &mut presetname) {
+                Ok(Settled::Accept(fullpaths)) => on_done(builder.finish(&purpose, fullpaths)),
+                Ok(Settled::ChooseAgain) => fltk_watch_chooser(builder, purpose, startdir, presetname, on_done),
+                Ok(Settled::Cancel) => on_done(Ok(None)),
+                Err(err) => on_done(Err(err)),
+            }
+        });
+    }
 
-        let combined_filter = fltk_build_dialogfltr(&extnsnvec);
-        fchooser.set_filter(&combined_filter);
-        // endregion
+    /// Runs `task` once, from the event loop, as soon as the loop is next
+    /// free.  `add_timeout3` wants a callback it can call again, so the task
+    /// is kept in an `Option` and taken out on the first call.
+    fn fltk_defer_once<F: FnOnce() + 'static>(task: F) {
+        let mut task = Some(task);
+        app::add_timeout3(0.0, move |_| {
+            let task = match task.take() {
+                Some(task) => task,
+                None => return,
+            };
+            task();
+        });
+    }
 
-        fchooser.show();
+    /// How one showing of the chooser ended, once the pick has been checked.
+    enum Settled {
+        Accept(Vec<String>),
+        ChooseAgain,
+        Cancel,
+    }
 
-        let path = fchooser.filename().to_str().unwrap().to_string();
-        path
+    /// What the user wants done with a save path, once asked about overwriting.
+    enum OverwriteChoice {
+        Use(String),
+        ChooseAgain,
+        Cancel,
+    }
+
+    /// Asks the user what to do if `fullpath` already exists, in the terminal
+    /// if `interm` is set.  A path that doesn't exist yet is used without asking.
+    fn fltk_confirm_overwrite(fullpath: &str, interm: bool) -> Result<OverwriteChoice, LibFileError> {
+        let exists = Path::new(fullpath).try_exists()
+            .map_err(|err| LibFileError::from_io(err, fullpath))?;
+        if !exists {
+            return Ok(OverwriteChoice::Use(fullpath.to_string()));
+        }
+
+        let question = format!("The file\n\n{}\n\nalready exists.  What do you want to do?", fullpath);
+        let answer = match interm {
+            true => {
+                let opts = vec!["Overwrite".to_string(), "Keep both (auto-number)".to_string(),
+                                "Choose again".to_string(), "Cancel".to_string()];
+                Some(util_activity_menu(&opts, &format!("\n {}", question)) as i32 - 1)
+            }
+            false => dialog::choice2_default(&question, "Overwrite", "Keep both (auto-number)", "Choose again"),
+        };
+        match answer {
+            Some(0) => Ok(OverwriteChoice::Use(fullpath.to_string())),
+            Some(1) => Ok(OverwriteChoice::Use(file_next_free_path(fullpath)?)),
+            Some(2) => Ok(OverwriteChoice::ChooseAgain),
+            _ => Ok(OverwriteChoice::Cancel),  // The question was closed without an answer.
+        }
+    }
+
+    /// The start directory and preset name for reopening a dialog after
+    /// `fullpath` was turned down:  the directory `fullpath` is in (or
+    /// `startdir`, if it has none) and its file name.
+    fn fltk_choose_again_from(fullpath: &str, startdir: String) -> Result<(String, String), LibFileError> {
+        let chosen = Path::new(fullpath);
+        let dir = match chosen.parent() {
+            Some(dir) => dir.to_string_lossy().into_owned(),
+            None => startdir,
+        };
+        Ok((dir, fltk_path_to_fname(chosen)?))
+    }
+
+    /// Tells the user why a chosen path was turned down by a validator.
+    fn fltk_show_rejection(fullpath: &str, reason: &str, interm: bool) {
+        let message = format!("The file\n\n{}\n\ncan't be used:  {}", fullpath, reason);
+        match interm {
+            true => println!("\n {} \n", message),
+            false => dialog::alert_default(&message),
+        }
+    }
+
+    /// Converts a chosen path to a `String`, failing if it is not valid UTF-8.
+    fn fltk_path_to_string(path: &Path) -> Result<String, LibFileError> {
+        match path.to_str() {
+            Some(pathstr) => Ok(pathstr.to_string()),
+            None => Err(LibFileError::InvalidUtf8(path.to_string_lossy().into_owned())),
+        }
+    }
+
+    /// Pulls the file name off the end of a chosen path.
+    fn fltk_path_to_fname(path: &Path) -> Result<String, LibFileError> {
+        let filename = match path.file_name() {
+            Some(filename) => filename,
+            None => return Err(LibFileError::InvalidPath(path.to_string_lossy().into_owned())),
+        };
+        match filename.to_str() {
+            Some(fname) => Ok(fname.to_string()),
+            None => Err(LibFileError::InvalidUtf8(path.to_string_lossy().into_owned())),
+        }
     }
 
-    /*
-    /// Opens a native file save dialog for the user to select a location
-    /// and name for saving a file, with pre-suggested parameters and a file extension filter.
-    ///
-    /// # Parameters
-    /// - `sggstdpath`: A reference to a string that specifies the suggested directory to open in the file dialog.
-    ///                 If the directory does not exist, it defaults to the user's home directory.
-    /// - `sggstdname`: A reference to a string that specifies the suggested default file name.
-    /// - `sggstdextnsn`: A reference to a string that defines the file extension or filter type for the save dialog.
-    ///                   This may include the `*.` prefix (e.g., `*.txt`) but is not required.
-    /// - `wintitle`: A reference to a string that specifies the title of the file save dialog window.
-    ///
-    /// # Returns
-    /// Returns a `String` that represents the full path of the file chosen or saved by the user.
-    ///
-    /// # Behavior
-    /// - Ensures the suggested directory (`sggstdpath`) exists before launching the file dialog.
-    /// - Uses the suggested directory and file name as defaults in the save dialog but allows the user to modify them.
-    /// - Automatically appends the provided file extension (`sggstdextnsn`) to the suggested file name if it is not already included.
-    /// - Sets a filter in the file dialog to display only files matching the provided extension and also adds an
-    ///   "All Files" filter option for displaying all files.
-    /// - Displays the dialog with the title provided in `wintitle`.  That title can also serve as a
-    ///     prompt to help guide the user.
-    ///
-    /// # Notes
-    /// - The `sggstdpath` and `sggstdname` parameters are suggestions only; the user can change both during the browsing process.
-    /// - If an invalid directory is specified in `sggstdpath`, it defaults to the user's home directory.
-    ///
-    /// # Example
-    ///     fn main() {
-    ///         let usedir = "/home/jtreagan/programming/mine/qbnk_rb7/src/qbnk_data/lists";
-    ///         let path = file_browse_tosave(usedir, "fakefilename", "txt");
-    ///         println!("\n {} \n", path);
-    ///     }
-    ///
-    /// # Dependencies
-    /// - This function utilizes `dialog::NativeFileChooser` for handling the native file save dialog.
-    /// - The function requires utilities like `dir_check_valid` to validate the suggested directory.
-    pub fn file_browse_tosave_old(sggstdpath: &str, sggstdname: &str, sggstdextnsn: &str) -> String {
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::{fs, process};
+
+        /// A fresh scratch directory for one test.
+        fn scratch_dir(name: &str) -> PathBuf {
+            let dir = env::temp_dir().join(format!("lib_file_fltk_{}_{}", name, process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).expect("The scratch directory can be made.");
+            dir
+        }
 
-        // region Note:
-        //          The passed string `sggstdpath` should be a suggested directory for saving a
-        //          file using the suggested `sggstdname` for a file name.  Note that both
-        //          variables are "suggestions" allowing for the user to change either.
-        // endregion
-        
-        // region Check that the passed directory exists and `startpath` is ready.
-        let track = dir_check_valid(&mut sggstdpath.to_string());  // Defaults to home directory on err.
-        let startpath = Path::new(track.as_str());
-        // endregion
+        fn scripted(answers: Vec<ScriptedAnswer>) -> Rc<RefCell<ScriptedRunner>> {
+            Rc::new(RefCell::new(ScriptedRunner::new(answers)))
+        }
 
-        println!("FBW1");
+        fn lists_and_vrbls() -> FilterSet {
+            FilterSet::new(&[("Lists", "*.lst"), ("Variables", "*.vrbl")])
+                .expect("The filters are written correctly.")
+                .with_all_files()
+        }
 
-        // region Call a dialog browser and set it to the passed directory.
-        let mut fchooser = dialog::NativeFileChooser
-                                ::new(dialog::NativeFileChooserType
-                                ::BrowseSaveFile);
-        fchooser.set_directory(&startpath).expect("Cannot set directory.");
-        // endregion
+        #[test]
+        fn dropped_uris_are_decoded() {
+            let payload = "file:///home/user/banks/Algebra%201.bnk\r\nfile:///home/user/%C3%A9t%C3%A9/x%2Fy.bnk\r\n";
+            assert_eq!(fltk_parse_dropped_paths(payload), vec![
+                PathBuf::from("/home/user/banks/Algebra 1.bnk"),
+                PathBuf::from("/home/user/\u{e9}t\u{e9}/x/y.bnk"),
+            ]);
+            assert_eq!(fltk_parse_dropped_paths("file:///tmp/100%.bnk"), vec![PathBuf::from("/tmp/100%.bnk")]);
+        }
 
-        println!("FBW2");
+        #[test]
+        fn dropped_uri_hosts_and_kde_single_slash_are_stripped() {
+            let payload = "file://localhost/tmp/a.bnk\nfile:/tmp/b.bnk\nFILE:///tmp/c.bnk\nfile://tmp\nfile:tmp\n";
+            assert_eq!(fltk_parse_dropped_paths(payload), vec![
+                PathBuf::from("/tmp/a.bnk"),
+                PathBuf::from("/tmp/b.bnk"),
+                PathBuf::from("/tmp/c.bnk"),
+            ]);
+        }
 
-        // region  Add the passed extension to the suggested file name.
-        let usename;
-        if sggstdextnsn != "" {
-            let ext_to_append = sggstdextnsn.strip_prefix("*.").unwrap_or(sggstdextnsn);
-            usename = format!("{}.{}", sggstdname, ext_to_append);
-        }  else {
-            usename = sggstdname.to_string();
+        #[test]
+        fn dropped_comments_blanks_and_other_uris_are_skipped() {
+            let payload = "# Dropped from the file manager\r\n\r\nhttp://example.com/a.bnk\r\n/tmp/plain.bnk\r\n\0";
+            assert_eq!(fltk_parse_dropped_paths(payload), vec![PathBuf::from("/tmp/plain.bnk")]);
         }
-        fchooser.set_preset_file(usename.as_str());
-        // endregion
 
-        println!("FBW3");
+        #[test]
+        fn filter_sets_reject_bad_entries_when_built() {
+            let bad: [&[(&str, &str)]; 5] = [
+                &[("", "*.lst")],
+                &[("Lists", "")],
+                &[("Lists", "*.lst;;*.txt")],
+                &[("Lists", "*.lst"), ("Lists", "*.txt")],
+                &[("Lists\tand more", "*.lst")],
+            ];
+            for pairs in bad {
+                assert!(matches!(FilterSet::new(pairs), Err(LibFileError::InvalidFilter(_))), "{:?}", pairs);
+            }
+            assert!(FilterSet::new(&[]).is_ok_and(|filters| filters.is_empty()));
+        }
 
-        // region Create the filter string & set the filter.
+        #[test]
+        fn filter_patterns_are_normalised_and_rendered_for_fltk() {
+            let filters = FilterSet::new(&[("Lists", "lst; .txt"), ("Banks", "*.bnk"), ("Mixed", "*.lst;notes*")])
+                .expect("The filters are written correctly.");
+            let entries = filters.entries();
+
+            assert_eq!(entries[0].patterns, vec!["*.lst", "*.txt"]);
+            assert_eq!(entries[0].fltk_pattern(), "*.{lst,txt}");
+            assert_eq!(entries[1].fltk_pattern(), "*.bnk");
+            assert_eq!(entries[2].fltk_pattern(), "{*.lst,notes*}");
+            assert_eq!(filters.fltk_filter(), "Lists\t*.{lst,txt}\nBanks\t*.bnk\nMixed\t{*.lst,notes*}");
+            assert_eq!(entries[0].default_extension(), Some("lst"));
+        }
 
-        // todo: Pass the extension suggestion in a vector that can include multiple extensions.
-        //      Include an extension title in that vector.  Would a hashmap be a better choice?
+        #[test]
+        fn all_files_and_default_index_are_kept_in_range() {
+            let filters = lists_and_vrbls();
+            assert_eq!(filters.entries().len(), 3);
+            assert!(filters.entries()[2].is_all_files());
+            assert_eq!(filters.clone().with_all_files().entries().len(), 3);  // Not added twice.
+
+            assert_eq!(filters.default_index(), 0);
+            let filters = filters.with_default(2).expect("Index 2 is the \"All Files\" entry.");
+            assert_eq!(filters.default_entry().map(|entry| entry.label.as_str()), Some("All Files"));
+            assert!(matches!(filters.with_default(3), Err(LibFileError::InvalidFilter(_))));
+        }
 
-        let combined_filter;
-        if sggstdextnsn == "" {
-            combined_filter = "All Files\t*.*".to_string();
+        #[test]
+        fn empty_extension_filter_shows_every_file() {
+            assert!(fltk_extension_filter("").is_ok_and(|filters| filters.is_empty()));
+            assert!(fltk_extension_filter("  ").is_ok_and(|filters| filters.is_empty()));
+            let filters = fltk_extension_filter("*.lst").expect("The extension is written correctly.");
+            assert_eq!(filters.entries()[0].patterns, vec!["*.lst"]);
+        }
 
+        #[test]
+        fn stale_start_dir_falls_back_to_home() {
+            let runner = scripted(vec![ScriptedAnswer::Cancel]);
+            let result = DialogBuilder::new(DialogMode::Open)
+                .start_dir("/no/such/dir/anywhere")
+                .backend(DialogBackend::Custom(runner.clone()))
+                .show();
 
-        }  else {
-            let useext;
-            if !sggstdextnsn.starts_with("*.") {
-                useext = format!("*.{}", sggstdextnsn);
-            } else {
-                useext = sggstdextnsn.to_string();
-            }
-            combined_filter = format!("Type   \t{}\nAll Files   \t*.*", useext);
+            assert!(matches!(result, Ok(None)));
+            assert_eq!(runner.borrow().requests()[0].startdir, dir_get_home());
         }
 
-        fchooser.set_filter(&combined_filter);
-        // endregion
+        #[test]
+        fn start_dir_naming_a_file_opens_its_directory() {
+            let dir = scratch_dir("startfile");
+            let file = dir.join("algebra.lst");
+            fs::write(&file, "").expect("The scratch file can be written.");
 
-        println!("FBW4");
+            let runner = scripted(vec![ScriptedAnswer::Cancel]);
+            let _ = DialogBuilder::new(DialogMode::Open)
+                .start_dir(&file.to_string_lossy())
+                .backend(DialogBackend::Custom(runner.clone()))
+                .show();
 
-        fchooser.show();
+            assert_eq!(runner.borrow().requests()[0].startdir, dir.to_string_lossy());
+            let _ = fs::remove_dir_all(&dir);
+        }
 
-        println!("FBW5");
+        #[test]
+        fn save_name_gets_the_picked_filters_extension() {
+            let dir = scratch_dir("saveext");
+            let bare = dir.join("slope").to_string_lossy().into_owned();
+            let runner = scripted(vec![
+                ScriptedAnswer::ChosenWithFilter(vec![bare.clone()], 1),
+                ScriptedAnswer::ChosenWithFilter(vec![bare.clone()], 2),
+            ]);
+            let builder = DialogBuilder::new(DialogMode::Save)
+                .start_dir(&dir.to_string_lossy())
+                .preset_name("slope")
+                .filters(&lists_and_vrbls())
+                .backend(DialogBackend::Custom(runner.clone()));
+
+            assert_eq!(builder.show().ok().flatten(), Some(format!("{}.vrbl", bare)));
+            assert_eq!(builder.show().ok().flatten(), Some(bare.clone()));  // "All Files" adds nothing.
+            assert_eq!(runner.borrow().requests()[0].presetname, "slope.lst");
+
+            runner.borrow_mut().push_answer(ScriptedAnswer::ChosenUnknownFilter(vec![bare.clone()]));
+            assert_eq!(builder.show().ok().flatten(), Some(bare));  // No filter known, so none is guessed.
+            let _ = fs::remove_dir_all(&dir);
+        }
 
-        let path = fchooser.filename().to_str().unwrap().to_string();
+        #[test]
+        fn cancel_and_errors_are_reported() {
+            let runner = scripted(vec![
+                ScriptedAnswer::Cancel,
+                ScriptedAnswer::Error(LibFileError::Dialog("No chooser.".to_string())),
+            ]);
+            let builder = DialogBuilder::new(DialogMode::Open)
+                .backend(DialogBackend::Custom(runner.clone()));
+
+            assert!(matches!(builder.show(), Ok(None)));
+            assert!(matches!(builder.show(), Err(LibFileError::Dialog(_))));
+            assert!(matches!(builder.show(), Err(LibFileError::Dialog(_))));  // The answers ran out.
+        }
 
-        println!("FBW6");
+        #[test]
+        fn rejected_path_reopens_in_the_same_directory() {
+            let dir = scratch_dir("validate");
+            let otherdir = scratch_dir("validate_other");
+            let wrong = dir.join("wrong.lst").to_string_lossy().into_owned();
+            let right = dir.join("right.lst").to_string_lossy().into_owned();
+            let runner = scripted(vec![
+                ScriptedAnswer::Chosen(vec![wrong]),
+                ScriptedAnswer::Chosen(vec![right.clone()]),
+            ]);
+
+            let result = DialogBuilder::new(DialogMode::Open)
+                .start_dir(&otherdir.to_string_lossy())
+                .backend(DialogBackend::Custom(runner.clone()))
+                .validate(|path| match path.ends_with("right.lst") {
+                    true => Validation::Accept,
+                    false => Validation::Reject("Not that one.".to_string()),
+                })
+                .show();
+
+            assert_eq!(result.ok().flatten(), Some(right));
+            let runner = runner.borrow();
+            assert_eq!(runner.requests().len(), 2);
+            assert_eq!(runner.requests()[0].startdir, otherdir.to_string_lossy());
+            assert_eq!(runner.requests()[1].startdir, dir.to_string_lossy());
+            assert_eq!(runner.rejections(), ["Not that one.".to_string()]);
+            let _ = fs::remove_dir_all(&dir);
+            let _ = fs::remove_dir_all(&otherdir);
+        }
+    }
 
-        path
+}  // End of file_fltk module.
+
+/// # Widgets for browsing files inside your own windows.
+///
+pub mod file_widgets {
+    use fltk::{app, browser::HoldBrowser, enums::CallbackTrigger, prelude::*};
+    use fltk::tree::{Tree, TreeItem, TreeReason, TreeSelect};
+    use std::{cell::RefCell, collections::HashMap, fs, path::Path, rc::Rc};
+    use crate::dir_mngmnt::{dir_resolve, dir_resolve_or_home, PathAccept, PathFallback};
+    use crate::file_errors::LibFileError;
+    use crate::file_fltk::FilterSet;
+    use crate::file_mngmnt::file_get_dir_list;
+
+    /// A callback that is handed the full path of a file.
+    type PathCallback = Option<Box<dyn FnMut(&str)>>;
+
+    /// What a `FileListPanel` shares with its browser's callback.
+    struct PanelState {
+        dir: String,
+        filters: FilterSet,
+        paths: Vec<String>,
+        on_select: PathCallback,
+        on_activate: PathCallback,
     }
-    */  // Deleteme later.
 
-    /// Opens a native directory chooser dialog, sets its starting path and window title,
-    /// and returns the selected directory path as a `String`.
-    ///
-    /// # Arguments
+    /// A list of the files in one directory, for a side panel that stays
+    /// open next to your work rather than popping up like a dialog.
     ///
-    /// * `sggstdpath` - A mutable string slice representing the initial directory path.
-    ///   If the provided path is invalid, it defaults to the user's home directory.
-    /// * `wintitle` - A string slice representing the title of the dialog window.
-    ///
-    /// # Returns
-    ///
-    /// This function returns the selected directory path as a `String`.
-    /// If no selection is made or an error occurs during directory selection,
-    /// it may result in unexpected behavior.
+    /// Only files matching one of the entries of a `FilterSet` are listed
+    /// (all files if the set is empty).  The list is read when the panel is
+    /// built and again whenever `refresh()` or `set_dir()` is called.
+    /// Clicking a file calls the `on_select` callback, and double-clicking it
+    /// (or pressing Enter on it) calls the `on_activate` callback.  Both are
+    /// handed the file's full path.
     ///
-    /// # Panics
+    /// Cloning a panel gives another handle to the same panel, so a clone can
+    /// be moved into other widgets' callbacks.
     ///
-    /// This function will panic if the directory passed to `fchooser.set_directory()`
-    /// is invalid and cannot be set.
-    ///
-    /// # Examples
+    /// # Example
     ///
-    ///     fn main() {
-    ///         let usedir = "/home/jtreagan/programming/mine/qbnk_rb7/src/qbnk_data/lists";
-    ///         let path = file_pathonly(usedir, "TITLE TITLE TITLE");
-    ///         println!("\n {} \n", path);
+    /// ```ignore
+    /// fn main() {
+    ///     let app = app::App::default();
+    ///     let mut win = window::Window::new(100, 100, 600, 400, "Banks");
+    ///     let filters = FilterSet::new(&[("Banks", "*.bnk"), ("Lists", "*.lst")])
+    ///         .expect("The filters are written correctly.");
+    ///     let mut panel = FileListPanel::new(0, 0, 200, 400, "/home/user/banks", &filters);
+    ///     win.end();
+    ///     win.show();
+    ///
+    ///     panel.on_select(|path| println!("Selected {}", path));
+    ///     panel.on_activate(|path| println!("Opening {}", path));
+    ///     match panel.refresh() {
+    ///         Ok(()) => app.run().unwrap(),
+    ///         Err(err) => println!("{}", err),
     ///     }
+    /// }
+    /// ```
     ///
-    /// # Notes
-    ///
-    /// This function relies on `dialog::NativeFileChooser` for platform-native file chooser dialogs
-    /// and uses the helper function `dir_normalize_path` to handle and sanitize the input directory path.
-    pub fn file_pathonly(mut sggstdpath: &str, wintitle: &str) -> String {
+    #[derive(Clone)]
+    pub struct FileListPanel {
+        browser: HoldBrowser,
+        state: Rc<RefCell<PanelState>>,
+    }
 
-        // region Check that the passed directory exists and `startpath` is ready.
-        let track = dir_normalize_path(&mut sggstdpath);  // Defaults to home directory on err.
-        let startpath = Path::new(track.as_str());
-        // endregion
+    impl FileListPanel {
+        /// Builds the panel at the given position and size, in the current
+        /// group, listing `startdir`.  A path to a file lists that file's
+        /// directory; a path that can't be resolved lists the home directory.
+        /// If the directory can't be read, the panel starts empty.
+        pub fn new(x: i32, y: i32, w: i32, h: i32, startdir: &str, filters: &FilterSet) -> FileListPanel {
+            let mut browser = HoldBrowser::new(x, y, w, h, None);
+            browser.set_trigger(CallbackTrigger::Changed | CallbackTrigger::NotChanged);
+
+            let state = Rc::new(RefCell::new(PanelState {
+                dir: dir_resolve_or_home(startdir),
+                filters: filters.clone(),
+                paths: Vec::new(),
+                on_select: None,
+                on_activate: None,
+            }));
+
+            let usestate = state.clone();
+            browser.set_callback(move |brwsr| {
+                let line = brwsr.value();
+                let path = match usize::try_from(line - 1) {
+                    Ok(index) => usestate.borrow().paths.get(index).cloned(),
+                    Err(_) => None,  // Nothing is selected.
+                };
+                let path = match path {
+                    Some(path) => path,
+                    None => return,
+                };
+
+                let activated = app::event_clicks()
+                    || (app::event() == fltk::enums::Event::KeyDown && app::event_key() == fltk::enums::Key::Enter);
+                fltk_panel_fire(&usestate, activated, &path);
+            });
+
+            let mut panel = FileListPanel { browser, state };
+            let _ = panel.refresh();  // An unreadable start directory just leaves the panel empty.
+            panel
+        }
 
-        // region Call a dialog browser, set it to the passed directory & set the title.
+        /// Sets the callback for a single click on a file.
+        pub fn on_select<F: FnMut(&str) + 'static>(&mut self, callback: F) {
+            self.state.borrow_mut().on_select = Some(Box::new(callback));
+        }
 
-        let mut fchooser = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseDir);
-        fchooser.set_directory(&startpath).expect("Cannot set directory.");
-        fchooser.set_title(wintitle);
-        fchooser.show();
+        /// Sets the callback for a double-click, or Enter, on a file.
+        pub fn on_activate<F: FnMut(&str) + 'static>(&mut self, callback: F) {
+            self.state.borrow_mut().on_activate = Some(Box::new(callback));
+        }
 
-        // endregion
+        /// Lists a different directory, resolved the same way as in `new()`.
+        pub fn set_dir(&mut self, dir: &str) -> Result<(), LibFileError> {
+            self.state.borrow_mut().dir = dir_resolve_or_home(dir);
+            self.refresh()
+        }
+
+        /// The directory being listed.
+        pub fn dir(&self) -> String {
+            self.state.borrow().dir.clone()
+        }
+
+        /// Lists only the files matching `filters` from now on.
+        pub fn set_filters(&mut self, filters: &FilterSet) -> Result<(), LibFileError> {
+            self.state.borrow_mut().filters = filters.clone();
+            self.refresh()
+        }
 
-        let pathonly = fchooser.filename().to_str().unwrap().to_string();
-        pathonly
+        /// Reads the directory again and redraws the list.  The selected file
+        /// stays selected if it is still there.  If the directory can't be
+        /// read, the list is emptied and the error is returned.
+        pub fn refresh(&mut self) -> Result<(), LibFileError> {
+            let selected = self.selected();
+            let (dir, filters) = {
+                let state = self.state.borrow();
+                (state.dir.clone(), state.filters.clone())
+            };
+
+            self.browser.clear();
+            self.state.borrow_mut().paths.clear();
+            let mut fnames = file_get_dir_list(&dir)?;
+
+            fnames.retain(|fname| filters.is_empty() || filters.entries().iter().any(|entry| entry.matches(fname)));
+            fnames.sort_by_key(|fname| fname.to_lowercase());
+
+            let mut paths = Vec::new();
+            for fname in fnames {
+                match fname.starts_with('@') {
+                    true => self.browser.add(&format!("@.{}", fname)),  // `@.` keeps the name from being read as formatting.
+                    false => self.browser.add(&fname),
+                }
+                paths.push(Path::new(&dir).join(&fname).to_string_lossy().into_owned());
+            }
+
+            let reselect = match &selected {
+                Some(selected) => paths.iter().position(|path| path == selected).map_or(0, |index| index as i32 + 1),
+                None => 0,
+            };
+            self.state.borrow_mut().paths = paths;
+            if reselect > 0 {
+                self.browser.select(reselect);
+            }
+            self.browser.redraw();
+            Ok(())
+        }
+
+        /// The full path of the selected file, if one is selected.
+        pub fn selected(&self) -> Option<String> {
+            match usize::try_from(self.browser.value() - 1) {
+                Ok(index) => self.state.borrow().paths.get(index).cloned(),
+                Err(_) => None,
+            }
+        }
+
+        /// The full paths of every file listed, in the order shown.
+        pub fn paths(&self) -> Vec<String> {
+            self.state.borrow().paths.clone()
+        }
+
+        /// The browser the panel draws in, for resizing, hiding, or putting
+        /// into layouts.
+        pub fn browser(&self) -> HoldBrowser {
+            self.browser.clone()
+        }
     }
 
-    /// A function that presents a native file chooser dialog to the user, allowing them to browse
-    /// for a file starting from a specified directory, and returns the selected file's name only.
-    ///
-    /// This function takes two inputs: a mutable reference to a string representing the starting directory
-    /// and a string for the window's title. It normalizes the directory path, initializes the native
-    /// file chooser dialog, sets its initial directory to the given path, and allows the user to select a file.
-    /// The name of the selected file (with no accompanying path information) is then extracted and returned as a `String`.
+    /// Calls a panel's select or activate callback.  The callback is taken
+    /// out of the panel while it runs, so it can safely call back into the
+    /// panel (to refresh it, say).
+    fn fltk_panel_fire(state: &Rc<RefCell<PanelState>>, activated: bool, path: &str) {
+        let callback = match activated {
+            true => state.borrow_mut().on_activate.take(),
+            false => state.borrow_mut().on_select.take(),
+        };
+        let mut callback = match callback {
+            Some(callback) => callback,
+            None => return,
+        };
+
+        callback(path);
+
+        let mut usestate = state.borrow_mut();
+        let slot = match activated {
+            true => &mut usestate.on_activate,
+            false => &mut usestate.on_select,
+        };
+        if slot.is_none() {  // Keep any callback that was set while this one ran.
+            *slot = Some(callback);
+        }
+    }
+
+    /// The label of the stand-in child a folder gets until it is opened.
+    const TREE_PLACEHOLDER: &str = "...";
+
+    /// How many folder levels below a subfolder are searched for a matching
+    /// file before the subfolder is shown anyway.
+    const TREE_MATCH_DEPTH: usize = 2;
+
+    /// What a `DirTree` shares with its tree's callback.
+    struct TreeState {
+        rootdir: String,
+        showhidden: bool,
+        filters: FilterSet,
+        on_select: PathCallback,
+        /// Whether each folder searched to the bottom so far holds a file
+        /// matching `filters`.
+        matches: HashMap<String, bool>,
+    }
+
+    /// A tree of directories for picking a folder inside your own window,
+    /// rather than through `file_pathonly`'s dialog.
     ///
-    /// # Arguments
-    /// * `sggstdpath` - A mutable reference to a string slice representing the starting
-    ///   path. If invalid or unavailable, it defaults to the user's home directory.
-    /// * `wintitle` - The title of the window for the file chooser dialog.
+    /// Folders are read only when they are opened, so large trees cost
+    /// nothing until the user goes into them.  Until then every folder shows
+    /// as openable; one that turns out to be empty loses its open box.
+    /// Hidden (dot) folders are left out unless `set_show_hidden(true)` is
+    /// called.  Symbolic links to folders are not followed.
     ///
-    /// # Returns
-    /// A `String` containing the name of the selected file, which is extracted from the full path provided
-    /// by the file chooser. If the user does not select a file or the operation fails, the function may panic.
+    /// With a non-empty `FilterSet`, only folders holding a matching file
+    /// somewhere below them are shown.  Opening a folder searches each of
+    /// its subfolders, and two levels below them, until the first match, so
+    /// that costs more than an unfiltered open.  A subfolder with folders
+    /// below that depth is shown without looking further, so opening a large
+    /// tree such as `/` doesn't hold up the window.  Each answer is
+    /// remembered until the tree is reloaded.
     ///
-    /// # Panics
-    /// This function will panic in the following scenarios:
-    /// 1. If the set directory is invalid or cannot be set (this is handled through the dialog API).
-    /// 2. If the returned file path does not contain a valid file name (e.g., if the path is empty).
-    /// 3. If the extracted filename is not valid UTF-8.
+    /// Selecting a folder calls the `on_select` callback with its full path.
     ///
     /// # Example
-    ///     fn main() {
-    ///         let usedir = "/some/existing/directory";
-    ///         let path = file_nameonly(usedir, "TITLE TITLE TITLE");
-    ///         println!("\n {} \n", path);
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let app = app::App::default();
+    ///     let mut win = window::Window::new(100, 100, 300, 500, "Categories");
+    ///     let mut dirtree = DirTree::new(0, 0, 300, 500, "/home/user/banks");
+    ///     win.end();
+    ///     win.show();
+    ///
+    ///     let filters = FilterSet::new(&[("Banks", "*.bnk")])
+    ///         .expect("The filters are written correctly.");
+    ///     dirtree.on_select(|dir| println!("Category {}", dir));
+    ///     match dirtree.set_filters(&filters) {
+    ///         Ok(()) => app.run().unwrap(),
+    ///         Err(err) => println!("{}", err),
     ///     }
+    /// }
+    /// ```
     ///
-    /// Note: This function uses `dialog::NativeFileChooser` for presenting the file dialog
-    /// and `std::path::Path` for dealing with directory paths.
-    pub fn file_nameonly(mut sggstdpath: &str, wintitle: &str) -> String {
+    #[derive(Clone)]
+    pub struct DirTree {
+        tree: Tree,
+        state: Rc<RefCell<TreeState>>,
+    }
 
-        // region Check that the passed directory exists and `startpath` is ready.
-        let track = dir_normalize_path(&mut sggstdpath);  // Defaults to home directory on err.
-        let startpath = Path::new(track.as_str());
-        // endregion
+    impl DirTree {
+        /// Builds the tree at the given position and size, in the current
+        /// group, with `rootdir` at its root.  A path to a file uses that
+        /// file's directory; a path that can't be resolved uses the home
+        /// directory.  If the root can't be read, the tree shows only the root.
+        pub fn new(x: i32, y: i32, w: i32, h: i32, rootdir: &str) -> DirTree {
+            let mut tree = Tree::new(x, y, w, h, None);
+            tree.set_show_root(true);
+            tree.set_select_mode(TreeSelect::Single);
+
+            let state = Rc::new(RefCell::new(TreeState {
+                rootdir: dir_resolve_or_home(rootdir),
+                showhidden: false,
+                filters: FilterSet::default(),
+                on_select: None,
+                matches: HashMap::new(),
+            }));
+
+            let usestate = state.clone();
+            tree.set_callback(move |usetree| {
+                let item = match usetree.callback_item() {
+                    Some(item) => item,
+                    None => return,
+                };
+                match usetree.callback_reason() {
+                    TreeReason::Opened => fltk_tree_expand(usetree, &item, &usestate),
+                    TreeReason::Selected => {
+                        let path = fltk_tree_item_path(&item, &usestate.borrow().rootdir);
+                        fltk_tree_fire(&usestate, &path);
+                    }
+                    _ => {},
+                }
+            });
 
-        // region Call a dialog browser, set it to the passed directory & set the title.
+            let mut dirtree = DirTree { tree, state };
+            let _ = dirtree.reload();  // An unreadable root just leaves the tree bare.
+            dirtree
+        }
 
-        let mut fchooser = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseFile);
-        fchooser.set_directory(&startpath).expect("Cannot set directory.");
-        fchooser.set_title(wintitle);
-        fchooser.show();
+        /// Sets the callback for selecting a folder.
+        pub fn on_select<F: FnMut(&str) + 'static>(&mut self, callback: F) {
+            self.state.borrow_mut().on_select = Some(Box::new(callback));
+        }
 
-        // endregion
+        /// Puts a different directory at the root, resolved the same way as in `new()`.
+        pub fn set_root(&mut self, rootdir: &str) -> Result<(), LibFileError> {
+            self.state.borrow_mut().rootdir = dir_resolve_or_home(rootdir);
+            self.reload()
+        }
 
-        // region Extract the file name from the path.
+        /// The directory at the root of the tree.
+        pub fn root_dir(&self) -> String {
+            self.state.borrow().rootdir.clone()
+        }
 
-        let path = fchooser.filename();
-        let filename = path.file_name().expect("The path has no file available.");
-        let filename_str = filename.to_str().expect("The path is not valid UTF-8");
-        let filename_string: String = filename_str.to_string();
+        /// Shows or hides folders whose names start with a dot.
+        pub fn set_show_hidden(&mut self, showhidden: bool) -> Result<(), LibFileError> {
+            self.state.borrow_mut().showhidden = showhidden;
+            self.reload()
+        }
 
-        // endregion
+        /// Shows only folders holding a file that matches `filters` somewhere
+        /// below them.  An empty set shows every folder.
+        pub fn set_filters(&mut self, filters: &FilterSet) -> Result<(), LibFileError> {
+            self.state.borrow_mut().filters = filters.clone();
+            self.reload()
+        }
 
-        filename_string
-    }
+        /// Forgets every opened folder and reads the root again.
+        pub fn reload(&mut self) -> Result<(), LibFileError> {
+            self.state.borrow_mut().matches.clear();
+            let rootdir = self.state.borrow().rootdir.clone();
+            self.tree.clear();
+            self.tree.set_root_label(&rootdir);
+            let root = match self.tree.root() {
+                Some(root) => root,
+                None => return Err(LibFileError::Dialog("The tree has no root item.".to_string())),
+            };
+
+            let subdirs = fltk_tree_subdirs(&rootdir, &mut self.state.borrow_mut())?;
+            fltk_tree_fill(&mut self.tree, &root, subdirs);
+            self.tree.redraw();
+            Ok(())
+        }
 
-    /// Generates a dialog to choose a file, starting from a specified directory,
-    /// and returns the full path of the selected file.
-    ///
-    /// # Arguments
-    /// - `sggstdpath`: A mutable reference to a string slice representing the starting directory path.
-    ///   If the path is invalid or does not exist, it defaults to the home directory.
-    /// - `wintitle`: A string slice that sets the window title of the file chooser dialog.
-    ///
-    /// # Returns
-    /// - A `String` containing the full path of the selected file.
-    ///
-    /// # Behavior
-    /// 1. Validates and normalizes the given directory path (`sggstdpath`) to ensure it exists.
-    ///    If validation fails, defaults the starting path to the home directory.
-    /// 2. Displays a file chooser dialog starting from the provided (or adjusted) directory
-    ///    and assigns the given `wintitle` as the window's title.
-    /// 3. Retrieves the full path of the selected file and returns it as a string.
-    ///
-    /// # Panics
-    /// - If there is a problem setting the directory for the file chooser dialog, the function will
-    ///   panic with the message `"Cannot set directory."`.
-    ///
-    /// # Example
-    ///     fn main() {
-    ///         let usedir = "/home/jtreagan/programming/mine/qbnk_rb7/src/qbnk_data/lists";
-    ///         let path = file_fullpath(usedir, "TITLE TITLE TITLE");
-    ///         println!("\n {} \n", path);
-    ///         }
-    ///
-    /// # Notes
-    /// - The function uses the `dialog` crate to create a native file chooser dialog.
-    /// - The provided `sggstdpath` is validated and normalized using `dir_normalize_path`.
-    /// - The resulting file path is converted to a `String` and returned.
-    pub fn file_fullpath(mut sggstdpath: &str, wintitle: &str) -> String {
+        /// Opens every folder from the root down to `dir` and selects `dir`,
+        /// scrolling it into view.  The select callback is not called.
+        ///
+        /// Returns `LibFileError::NotFound` if `dir` isn't an existing folder
+        /// below the root, or if a folder on the way isn't shown (it is
+        /// hidden, or holds no file matching the filters).
+        pub fn open_dir(&mut self, dir: &str) -> Result<(), LibFileError> {
+            let rootdir = self.state.borrow().rootdir.clone();
+            let usedir = dir_resolve(dir, PathAccept::Dir, PathFallback::None)?.path;
+            let relative = match Path::new(&usedir).strip_prefix(&rootdir) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => return Err(LibFileError::NotFound(usedir)),
+            };
+
+            let mut item = match self.tree.root() {
+                Some(root) => root,
+                None => return Err(LibFileError::Dialog("The tree has no root item.".to_string())),
+            };
+            for part in relative.iter() {
+                fltk_tree_expand(&mut self.tree, &item, &self.state);
+                item.open();
+                item = match item.find_child_item(&part.to_string_lossy()) {
+                    Some(child) => child,
+                    None => return Err(LibFileError::NotFound(usedir)),
+                };
+            }
 
-        // region Check that the passed directory exists and `startpath` is ready.
-        let track = dir_normalize_path(&mut sggstdpath);  // Defaults to home directory on err.
+            self.tree.select_only(&item, false)?;
+            self.tree.show_item_middle(&item);
+            self.tree.redraw();
+            Ok(())
+        }
 
-        let startpath = Path::new(track.as_str());
-        // endregion
+        /// The full path of the selected folder, if one is selected.
+        pub fn selected_dir(&self) -> Option<String> {
+            let rootdir = self.state.borrow().rootdir.clone();
+            self.tree.first_selected_item().map(|item| fltk_tree_item_path(&item, &rootdir))
+        }
 
-        // region Call a dialog browser, set it to the passed directory & set the title.
+        /// The tree the folders are drawn in, for resizing, hiding, or putting
+        /// into layouts.
+        pub fn tree(&self) -> Tree {
+            self.tree.clone()
+        }
+    }
 
-        let mut fchooser = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseFile);
-        fchooser.set_directory(&startpath).expect("Cannot set directory.");
-        fchooser.set_title(wintitle);
-        fchooser.show();
+    /// Reads a folder's children the first time the folder is opened.
+    fn fltk_tree_expand(tree: &mut Tree, item: &TreeItem, state: &Rc<RefCell<TreeState>>) {
+        let unread = item.children() == 1
+            && item.child(0).and_then(|child| child.label()).as_deref() == Some(TREE_PLACEHOLDER);
+        if !unread {
+            return;
+        }
 
-        // endregion
+        let mut state = state.borrow_mut();
+        let dir = fltk_tree_item_path(item, &state.rootdir);
+        let subdirs = fltk_tree_subdirs(&dir, &mut state).unwrap_or_default();  // Unreadable folders just look empty.
+        tree.clear_children(item);
+        fltk_tree_fill(tree, item, subdirs);
+        tree.redraw();
+    }
 
-        // Extract the path from the file chooser.
-        let path = fchooser.filename().to_str().unwrap().to_string();
-        path
+    /// Adds one child per subdirectory under `item`.  Each child gets a
+    /// placeholder child, so it shows as openable without being read yet.
+    fn fltk_tree_fill(tree: &mut Tree, item: &TreeItem, subdirs: Vec<String>) {
+        for name in subdirs {
+            let mut child = match tree.insert(item, &name, item.children()) {
+                Some(child) => child,
+                None => continue,
+            };
+            let _ = tree.insert(&child, TREE_PLACEHOLDER, 0);
+            child.close();
+        }
     }
 
+    /// The names of the subdirectories of `dir` the tree should show, sorted.
+    fn fltk_tree_subdirs(dir: &str, state: &mut TreeState) -> Result<Vec<String>, LibFileError> {
+        let mut names: Vec<String> = fltk_tree_read_subdirs(dir, state.showhidden)?
+            .into_iter()
+            .filter(|name| {
+                state.filters.is_empty()
+                    || fltk_tree_holds_matches(&Path::new(dir).join(name).to_string_lossy(), TREE_MATCH_DEPTH, state)
+                        != Some(false)
+            })
+            .collect();
+        names.sort_by_key(|name| name.to_lowercase());
+        Ok(names)
+    }
 
-    // todo: If you add extension and file name fields to the above functions,
-    //          you can likely eliminate the need for the following functions.
-    
-    /// Browse to a desired directory, filter the files by the passed extension.
-    /// The returned string includes both the path and the file name.
-    ///
-    /// Note that the `extension` value must have format  `*.xxxxx`.
-    /// Note that a file must be highlighted before the dialog will close.
-    pub fn file_fullpath_fltr(mut usedir: &String, extension: &str) -> String {
+    /// Checks whether `dir`, or any folder up to `depth` levels below it,
+    /// holds a file matching the tree's filters.  Stops at the first match.
+    /// Returns `None` if no match was found but there are folders deeper
+    /// down that weren't searched.  Only sure answers are remembered.
+    fn fltk_tree_holds_matches(dir: &str, depth: usize, state: &mut TreeState) -> Option<bool> {
+        let known = state.matches.get(dir).copied();
+        if known.is_some() {
+            return known;
+        }
+
+        let fnames = file_get_dir_list(dir).unwrap_or_default();
+        let matched = fnames.iter().any(|fname| {
+            (state.showhidden || !fname.starts_with('.'))
+                && state.filters.entries().iter().any(|entry| entry.matches(fname))
+        });
+        let subdirs = match matched {
+            true => Vec::new(),
+            false => fltk_tree_read_subdirs(dir, state.showhidden).unwrap_or_default(),
+        };
+
+        // region Search the subfolders, or give up on them if they are too deep.
+        let found = match (matched, depth, subdirs.is_empty()) {
+            (true, _, _) => Some(true),
+            (false, _, true) => Some(false),
+            (false, 0, false) => None,
+            (false, _, false) => {
+                let mut found = Some(false);
+                for name in subdirs {
+                    match fltk_tree_holds_matches(&Path::new(dir).join(name).to_string_lossy(), depth - 1, state) {
+                        Some(true) => {
+                            found = Some(true);
+                            break;
+                        }
+                        Some(false) => {}
+                        None => found = None,
+                    }
+                }
+                found
+            }
+        };
+        // endregion
 
-        // Make sure the passed directory exists and `startpath` is ready.
-        let track = dir_check_valid(&mut usedir);  // Defaults to home directory on err.
-        let startpath = Path::new(track.as_str());
+        if let Some(sure) = found {
+            state.matches.insert(dir.to_string(), sure);
+        }
+        found
+    }
 
-        // Start dialog browser and set to the correct directory.
-        let mut dialog = dialog::NativeFileChooser::new(dialog
-                                        ::NativeFileChooserType
-                                        ::BrowseFile);
-        dialog.set_directory(&startpath).expect("Directory does not exist.");
-        dialog.set_filter(extension);
+    /// The names of the real (not linked) subdirectories of `dir`.
+    fn fltk_tree_read_subdirs(dir: &str, showhidden: bool) -> Result<Vec<String>, LibFileError> {
+        let entries = fs::read_dir(dir).map_err(|err| LibFileError::from_io(err, dir))?;
+
+        let mut names = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|err| LibFileError::from_io(err, dir))?;
+            let is_dir = match entry.file_type() {
+                Ok(filetype) => filetype.is_dir(),
+                Err(_) => false,
+            };
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if is_dir && (showhidden || !name.starts_with('.')) {
+                names.push(name);
+            }
+        }
+        Ok(names)
+    }
 
-        dialog.show();
+    /// Rebuilds a folder's full path from its labels, up to the root.
+    fn fltk_tree_item_path(item: &TreeItem, rootdir: &str) -> String {
+        let mut labels = Vec::new();
+        let mut current = Some(item.clone());
+        loop {
+            match current {
+                Some(useitem) if !useitem.is_root() => {
+                    labels.push(useitem.label().unwrap_or_default());
+                    current = useitem.parent();
+                }
+                _ => break,
+            }
+        }
 
-        let path = dialog.filename().to_str().unwrap().to_string();
-        path
+        let mut path = Path::new(rootdir).to_path_buf();
+        for label in labels.iter().rev() {
+            path.push(label);
+        }
+        path.to_string_lossy().into_owned()
     }
 
-    /// Browse to a desired directory, filter the files by the passed extension.
-    /// The returned string includes only the file name.
-    pub fn file_nameonly_fltr(mut usedir: &String, extension: &str) -> String {
-        // Note that the `extension` value must have format  `*.xxxxx`.
+    /// Calls a tree's select callback, taken out of the tree while it runs so
+    /// it can safely call back into the tree.
+    fn fltk_tree_fire(state: &Rc<RefCell<TreeState>>, path: &str) {
+        let mut callback = match state.borrow_mut().on_select.take() {
+            Some(callback) => callback,
+            None => return,
+        };
+        callback(path);
+
+        let mut usestate = state.borrow_mut();
+        if usestate.on_select.is_none() {  // Keep any callback that was set while this one ran.
+            usestate.on_select = Some(callback);
+        }
+    }
 
-        // Make sure the passed directory exists and `startpath` is ready.
-        let track = dir_check_valid(&mut usedir);  // Defaults to home directory on err.
-        let startpath = Path::new(track.as_str());
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::{env, process};
+
+        /// A tree state filtering on `*.bnk`, over a fresh scratch directory
+        /// holding `files` (paths relative to it).
+        fn scratch_state(name: &str, files: &[&str]) -> (TreeState, String) {
+            let dir = env::temp_dir().join(format!("lib_file_widgets_{}_{}", name, process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            for file in files {
+                let path = dir.join(file);
+                fs::create_dir_all(path.parent().expect("A scratch file has a parent."))
+                    .expect("The scratch directories can be made.");
+                fs::write(&path, "").expect("The scratch file can be written.");
+            }
+            let state = TreeState {
+                rootdir: dir.to_string_lossy().into_owned(),
+                showhidden: false,
+                filters: FilterSet::new(&[("Banks", "*.bnk")]).expect("The filters are written correctly."),
+                on_select: None,
+                matches: HashMap::new(),
+            };
+            (state, dir.to_string_lossy().into_owned())
+        }
 
+        #[test]
+        fn filtered_tree_searches_only_a_few_levels_down() {
+            let (mut state, dir) = scratch_state("treedepth", &["near/a/algebra.bnk", "far/a/b/c/geometry.bnk",
+                                                                "none/a/notes.txt", ".hidden/algebra.bnk"]);
+            let sub = |name: &str| Path::new(&dir).join(name).to_string_lossy().into_owned();
 
-        // Set the dialog browser to the correct directory.
-        let mut dialog = dialog::NativeFileChooser::new(dialog
-                                                    ::NativeFileChooserType
-                                                    ::BrowseFile);
-        dialog.set_directory(&startpath).expect("Directory does not exist.");
-        dialog.set_filter(extension);
+            assert_eq!(fltk_tree_holds_matches(&sub("near"), TREE_MATCH_DEPTH, &mut state), Some(true));
+            assert_eq!(fltk_tree_holds_matches(&sub("far"), TREE_MATCH_DEPTH, &mut state), None);
+            assert_eq!(fltk_tree_holds_matches(&sub("none"), TREE_MATCH_DEPTH, &mut state), Some(false));
+            assert_eq!(fltk_tree_subdirs(&dir, &mut state).ok(), Some(vec!["far".to_string(), "near".to_string()]));
 
-        dialog.show();
+            assert!(!state.matches.contains_key(&sub("far")));  // Only sure answers are remembered.
+            assert_eq!(state.matches.get(&sub("none")), Some(&false));
+            let _ = fs::remove_dir_all(&dir);
+        }
+    }
 
-        let path = dialog.filename();
+}  // End of file_widgets module.
 
-        let filename = path.file_name().expect("The path has no file available.");
-        let filename_str = filename.to_str().expect("The path is not valid UTF-8");
-        let filename_string: String = filename_str.to_string();
+/// # Side-by-side comparison of two text files.
+///
+pub mod sidebyside;
 
-        filename_string
-    }
+/// # An open-file window with a preview pane.
+///
+pub mod sideview;
 
-    /// Constructs a filter string for use in FLTK dialog functions based on pairs of labels and extensions.
-    ///
-    /// The `fltk_build_dialogfltr` function takes a vector of string slices (`&Vec<&str>`) containing alternating
-    /// labels and extensions, and produces a properly formatted filter string for use in FLTK file dialogs.
-    ///
-    /// # Parameters
-    ///
-    /// * `labelextns`: A vector of string slices containing alternating file type labels and their corresponding
-    ///   file extension patterns. Each label should be paired with its extension. Extensions can include wildcards
-    ///   (e.g. `*.txt`) or curly-braced patterns (e.g. `{*.jpg,*.png}`).
-    ///
-    /// # Returns
-    ///
-    /// Returns a formatted string with the label and extension/filter pattern pairs, each pair on a new line. The
-    /// format for each line is:
-    ///
-    /// ```text
-    /// label<TAB>extension_pattern
-    /// ```
-    ///
-    /// - Example:
-    ///
-    /// ```text
-    /// Text Files	*.txt
-    /// Images	{*.jpg,*.png}
-    /// ```
+/// # Functions dealing with directories.
+///
+pub mod dir_mngmnt {
+    use std::{collections::BTreeMap, env, fs, io, path::{Path, PathBuf}};
+    use lib_utils::input_utilities::input_string_prompt;
+    use crate::file_errors::LibFileError;
+
+    /// Remembers the last directory the user chose for each named purpose,
+    /// such as `"banks"`, `"lists"` or `"exports"`, and keeps it in a small
+    /// text file between runs.
     ///
-    /// # Panics
+    /// The file holds one `purpose<TAB>directory` line per purpose.  Lines
+    /// that don't fit that shape are skipped when loading.
     ///
-    /// This function does not panic, but will silently skip input strings that do not form valid label-extension pairs
-    /// (e.g., if the vector has an odd number of elements).
+    /// Install a store with `file_fltk::fltk_set_lastdirs()` and the dialogs
+    /// start in the remembered directories and update them on their own.
     ///
     /// # Example
     ///
-    ///     fn main() {
-    ///         let label_extensions = vec![
-    ///             "Text Files", "*.txt",
-    ///             "Images", "{*.jpg,*.png}",
-    ///             "Rust Files", "*.rs"
-    ///     ];
+    /// ```ignore
+    /// fn main() {
+    ///     let storepath = LastDirs::default_path("qbnk");
+    ///     let mut lastdirs = match LastDirs::load(&storepath) {
+    ///         Ok(lastdirs) => lastdirs,
+    ///         Err(err) => {
+    ///             println!("\n {} \n", err);
+    ///             return;
+    ///         }
+    ///     };
     ///
-    ///         let dialog_filter = fltk_build_dialogfltr(&label_extensions);
-    ///         println!("{}", dialog_filter);
+    ///     println!("Banks were last opened from {:?}", lastdirs.get("banks"));
+    ///     match lastdirs.set("banks", "/home/somebody/banks") {
+    ///         Ok(()) => {},
+    ///         Err(err) => println!("\n {} \n", err),
+    ///     }
+    ///     match lastdirs.save() {
+    ///         Ok(()) => {},
+    ///         Err(err) => println!("\n {} \n", err),
+    ///     }
     /// }
-    ///
-    /// Example output:
-    /// ```text
-    /// Text Files	*.txt
-    /// Images	{*.jpg,*.png}
-    /// Rust Files	*.rs
     /// ```
     ///
-    /// # Notes
-    ///
-    /// - If an extension doesn't start with `*.` or doesn't contain `{`, the function automatically prepends
-    ///   `*.` to the provided extension.
-    /// - The function ignores invalid input pairs without causing a runtime error.
-    ///
-    pub fn fltk_build_dialogfltr(labelextns: &Vec<&str>) -> String {
-        let mut parts = Vec::new();
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct LastDirs {
+        storepath: String,
+        dirs: BTreeMap<String, String>,
+    }
 
-        for pair in labelextns.chunks(2) {
-            match pair {  // I used a match here instead of `if let` because I don't like `if let`.
-                [label, extension] => {  // Ensure pattern starts with "*."
-                    let usextnsn = if !extension.starts_with("*.") && !extension.contains('{') {
-                        format!("*.{}", extension) // temporary string if needed
-                    }
-                    else {
-                        extension.to_string()
-                    };
-                    parts.push(format!("{}\t{}", label, usextnsn));
+    impl LastDirs {
+        /// Reads the store at `storepath`.  A missing file gives an empty
+        /// store, which is written there by the first `save()`.
+        pub fn load(storepath: &str) -> Result<LastDirs, LibFileError> {
+            let contents = match fs::read_to_string(storepath) {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+                Err(err) => return Err(LibFileError::from_io(err, storepath)),
+            };
+
+            let dirs = contents.lines()
+                .filter_map(|line| line.split_once('\t'))
+                .filter(|(purpose, dir)| !purpose.is_empty() && !dir.is_empty())
+                .map(|(purpose, dir)| (purpose.to_string(), dir.to_string()))
+                .collect();
+            Ok(LastDirs { storepath: storepath.to_string(), dirs })
+        }
+
+        /// The usual place to keep an application's store:  `lastdirs` in the
+        /// application's state directory (see `dir_get_state()`), such as
+        /// `<home>/.local/state/<appname>/lastdirs`.
+        pub fn default_path(appname: &str) -> String {
+            Path::new(&dir_get_state(appname)).join("lastdirs")
+                .to_string_lossy().into_owned()
+        }
+
+        /// The file this store is read from and saved to.
+        pub fn store_path(&self) -> &str {
+            &self.storepath
+        }
+
+        /// The remembered directory for `purpose`, if there is one.  It is
+        /// not checked; the directory may have been removed since.
+        pub fn get(&self, purpose: &str) -> Option<&str> {
+            self.dirs.get(purpose).map(|dir| dir.as_str())
+        }
+
+        /// Remembers `dir` for `purpose`.  Neither can hold a tab or a line
+        /// break, since those would break the file; such a call returns
+        /// `LibFileError::InvalidPath` and changes nothing.
+        pub fn set(&mut self, purpose: &str, dir: &str) -> Result<(), LibFileError> {
+            for text in [purpose, dir] {
+                if text.is_empty() || text.contains(['\t', '\n', '\r']) {
+                    return Err(LibFileError::InvalidPath(text.to_string()));
                 }
-                _ => {},
             }
+            self.dirs.insert(purpose.to_string(), dir.to_string());
+            Ok(())
         }
-        parts.join("\n")
-    }
 
+        /// Writes the store back to its file, creating the file's directory
+        /// if needed.
+        pub fn save(&self) -> Result<(), LibFileError> {
+            match Path::new(&self.storepath).parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => {
+                    fs::create_dir_all(parent)
+                        .map_err(|err| LibFileError::from_io(err, &parent.to_string_lossy()))?;
+                }
+                _ => {},
+            }
 
-}  // End of file_fltk module.
-
-/// # Functions dealing with directories.
-///
-pub mod dir_mngmnt {
-    use std::{env, fs, io, path::Path};
-    use lib_utils::input_utilities::input_string_prompt;
+            let mut contents = String::new();
+            for (purpose, dir) in &self.dirs {
+                contents.push_str(&format!("{}\t{}\n", purpose, dir));
+            }
+            fs::write(&self.storepath, contents)
+                .map_err(|err| LibFileError::from_io(err, &self.storepath))
+        }
+    }
 
     /// Retrieves the default home directory path of the current user based on the operating system.
     ///
@@ -644,135 +3343,391 @@ pub mod dir_mngmnt {
         }
     }
 
-    // region TODO'S
-    // todo: Could `dir_check_valid` and `dir_normalize_path` be combined into one function?
-    //       The only difference is that `dir_check_valid` returns the original path if it exists,
-    //       while `dir_normalize_path` returns the parent directory if the path is a file.
-    // todo: Could/should they be turned into methods?
-    // endregion
+    /// The kinds of per-application directory the XDG Base Directory spec names.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum BaseDir {
+        Config,
+        Data,
+        Cache,
+        State,
+    }
 
-    /// Validates the given directory path and ensures it is ready for use.
-    /// If the provided directory exists, its path is returned. Otherwise,
-    /// the function falls back to the user's home directory.
-    ///
-    /// # Arguments
-    ///
-    /// * `usedir` - A reference to a `String` containing the directory path to be checked.
-    ///
-    /// # Returns
+    /// Where an application keeps its settings:  `$XDG_CONFIG_HOME/<appname>`,
+    /// which is `<home>/.config/<appname>` unless overridden.
     ///
-    /// A `String` containing the valid directory path. If the provided path does not
-    /// exist, it returns the user's home directory path.
+    /// The four `dir_get_*` base-directory functions follow the XDG Base
+    /// Directory spec on Linux and other Unix-like systems.  An
+    /// `XDG_*_HOME` variable is used only if it holds an absolute path, as
+    /// the spec asks; otherwise the spec's default under `dir_get_home()` is
+    /// used.  Elsewhere they use the platform's usual places:
     ///
-    /// # Behavior
+    /// * **Windows**:  `%APPDATA%\<appname>` for config and data, and
+    ///   `%LOCALAPPDATA%\<appname>\cache` or `...\state` for cache and state.
+    ///   A missing variable falls back to `AppData\Roaming` or `AppData\Local`
+    ///   under `dir_get_home()`.
+    /// * **macOS**:  `~/Library/Application Support/<appname>` for config, data
+    ///   and state, and `~/Library/Caches/<appname>` for the cache.
     ///
-    /// - Verifies whether the directory specified by `usedir` exists.
-    /// - If the directory exists, the function returns the original directory path.
-    /// - If the directory does not exist:
-    ///   - An error message is printed to `stderr` indicating the invalid path.
-    ///   - The function defaults to the user's home directory, as obtained
-    ///     by the `dir_get_home` function, and returns that instead.
+    /// Like `dir_get_home()`, these don't check that the directory exists.
+    /// Pass the result to `dir_create_all()` before writing into it.
     ///
     /// # Example
-    ///     fn main() {
-    ///         let path = String::from("/nonexistent/path");
-    ///         let valid_path = dir_check_valid(&path);
-    ///         println!("Validated path: {}", valid_path); // Prints the home directory path
-    ///     }                                               if `/nonexistent/path` doesn't exist.
-    ///
-    /// # Notes
     ///
-    /// The function assumes the existence of a helper function `dir_get_home`,
-    /// which retrieves the user's home directory as a `String`. Ensure this
-    /// dependency is properly implemented.
+    /// ```ignore
+    /// fn main() {
+    ///     match dir_create_all(&dir_get_config("qbnk")) {
+    ///         Ok(configdir) => println!("Settings go in {}", configdir),  // /home/user/.config/qbnk
+    ///         Err(err) => println!("{}", err),
+    ///     }
+    /// }
+    /// ```
     ///
-    pub fn dir_check_valid(dirstring: &str) -> String {
+    pub fn dir_get_config(appname: &str) -> String {
+        dir_get_base(BaseDir::Config, appname)
+    }
 
-        // Make sure the directory exists and `trail` is ready for use.
-        let trail = Path::new(dirstring);
-        if trail.exists() {
-            dirstring.to_string().clone()
-        } else {   // If the directory doesn't exist, default to the home directory.
-            eprintln!("The path {} does not exist!", dirstring);
-            let track: String = dir_get_home();
-            track
-        }
+    /// Where an application keeps files the user works with or can't easily
+    /// recreate:  `$XDG_DATA_HOME/<appname>`, which is
+    /// `<home>/.local/share/<appname>` unless overridden.  See `dir_get_config()`
+    /// for the rules on each platform.
+    pub fn dir_get_data(appname: &str) -> String {
+        dir_get_base(BaseDir::Data, appname)
+    }
+
+    /// Where an application keeps files it can rebuild if they're deleted:
+    /// `$XDG_CACHE_HOME/<appname>`, which is `<home>/.cache/<appname>` unless
+    /// overridden.  See `dir_get_config()` for the rules on each platform.
+    pub fn dir_get_cache(appname: &str) -> String {
+        dir_get_base(BaseDir::Cache, appname)
+    }
+
+    /// Where an application keeps state that should survive a restart but
+    /// isn't worth backing up, such as history and recently used files:
+    /// `$XDG_STATE_HOME/<appname>`, which is `<home>/.local/state/<appname>`
+    /// unless overridden.  See `dir_get_config()` for the rules on each platform.
+    pub fn dir_get_state(appname: &str) -> String {
+        dir_get_base(BaseDir::State, appname)
+    }
+
+    /// Creates `dirpath` and any missing directories above it, and returns
+    /// it.  A directory that already exists is fine.
+    pub fn dir_create_all(dirpath: &str) -> Result<String, LibFileError> {
+        fs::create_dir_all(dirpath).map_err(|err| LibFileError::from_io(err, dirpath))?;
+        Ok(dirpath.to_string())
+    }
+
+    /// Works out one of the base directories for `appname`.
+    fn dir_get_base(kind: BaseDir, appname: &str) -> String {
+        let home = dir_get_home();
+        let from_env = |var: &str| match env::var(var) {
+            Ok(value) if Path::new(&value).is_absolute() => Some(value),
+            _ => None,
+        };
+
+        let basedir = if cfg!(windows) {
+            let (var, fallback) = match kind {
+                BaseDir::Config | BaseDir::Data => ("APPDATA", ["AppData", "Roaming"]),
+                BaseDir::Cache | BaseDir::State => ("LOCALAPPDATA", ["AppData", "Local"]),
+            };
+            let basedir = from_env(var).map(PathBuf::from)
+                .unwrap_or_else(|| Path::new(&home).join(fallback[0]).join(fallback[1]));
+            match kind {
+                BaseDir::Config | BaseDir::Data => basedir.join(appname),
+                BaseDir::Cache => basedir.join(appname).join("cache"),
+                BaseDir::State => basedir.join(appname).join("state"),
+            }
+        } else if cfg!(target_os = "macos") {
+            let library = Path::new(&home).join("Library");
+            match kind {
+                BaseDir::Cache => library.join("Caches").join(appname),
+                _ => library.join("Application Support").join(appname),
+            }
+        } else {
+            // Linux and other Unix-like systems
+            let (var, fallback) = match kind {
+                BaseDir::Config => ("XDG_CONFIG_HOME", ".config"),
+                BaseDir::Data => ("XDG_DATA_HOME", ".local/share"),
+                BaseDir::Cache => ("XDG_CACHE_HOME", ".cache"),
+                BaseDir::State => ("XDG_STATE_HOME", ".local/state"),
+            };
+            from_env(var).map(PathBuf::from)
+                .unwrap_or_else(|| Path::new(&home).join(fallback))
+                .join(appname)
+        };
+        basedir.to_string_lossy().into_owned()
     }
 
-    /// Normalizes a given directory string to resolve its path based on the context.
+    /// Expands a path the way a shell would, then makes it absolute.
+    ///
+    /// * `~` and `~/...` start from the user's home directory (`dir_get_home()`),
+    ///   and `~name/...` from the home directory of user `name`.
+    /// * `$NAME` and `${NAME}` are replaced by environment variables.  A name
+    ///   starts with a letter or `_`.  A `$` that isn't followed by a name is
+    ///   kept as it is, and so is `$$`.
+    /// * A path that is still relative is joined onto `basedir`.
+    ///
+    /// An empty path is returned empty, so callers can still treat a blank
+    /// answer as "cancel".
     ///
-    /// This function processes the input string and modifies it according to the following cases:
+    /// # Errors
+    ///
+    /// * `LibFileError::UnsetVariable` if the path names a variable that isn't set.
+    /// * `LibFileError::NotFound` if `~name` names a user with no known home directory.
     ///
-    /// ### Case 1: Input Path is a Valid Directory
-    /// - If the given string corresponds to an existing directory, the function returns the directory path
-    ///   as is.
+    /// # Example
     ///
-    /// ### Case 2: Input Path is a Valid File
-    /// - If the input string points to an existing file, the function returns the parent directory of that file.
+    /// ```ignore
+    /// fn main() {
+    ///     // With HOME=/home/user and QBNK_HOME=/srv/qbnk:
+    ///     println!("{:?}", dir_expand_path("~/banks", "/tmp"));           // Ok("/home/user/banks")
+    ///     println!("{:?}", dir_expand_path("$QBNK_HOME/lists", "/tmp"));  // Ok("/srv/qbnk/lists")
+    ///     println!("{:?}", dir_expand_path("lists", "/srv/qbnk"));        // Ok("/srv/qbnk/lists")
+    /// }
+    /// ```
     ///
-    /// ### Case 3: Input Path is Likely a Directory with an Inaccurate Ending
-    /// - If the input string might represent a directory but includes an inaccurate file name, the function
-    ///   attempts to resolve and return the parent directory.
+    pub fn dir_expand_path(dirstring: &str, basedir: &str) -> Result<String, LibFileError> {
+        if dirstring.is_empty() {
+            return Ok(String::new());
+        }
+
+        // region Tilde:  `~`, `~/rest` or `~name/rest`.
+        let expanded = match dirstring.strip_prefix('~') {
+            Some(rest) => {
+                let (user, rest) = match rest.find(['/', '\\']) {
+                    Some(slash) => rest.split_at(slash),
+                    None => (rest, ""),
+                };
+                let home = match user.is_empty() {
+                    true => dir_get_home(),
+                    false => match dir_user_home(user) {
+                        Some(home) => home,
+                        None => return Err(LibFileError::NotFound(format!("~{}", user))),
+                    },
+                };
+                format!("{}{}", home, rest)
+            }
+            None => dirstring.to_string(),
+        };
+        // endregion
+
+        let expanded = dir_expand_vars(&expanded, dirstring)?;
+
+        match Path::new(&expanded).is_absolute() {
+            true => Ok(expanded),
+            false => Ok(Path::new(basedir).join(&expanded).to_string_lossy().into_owned()),
+        }
+    }
+
+    /// Same as `dir_expand_path`, with relative paths taken from the current
+    /// working directory (or the home directory, if that can't be read).
+    /// The other functions in this module that take a directory use this.
+    pub fn dir_expand(dirstring: &str) -> Result<String, LibFileError> {
+        let basedir = match env::current_dir() {
+            Ok(dir) => dir.to_string_lossy().into_owned(),
+            Err(_) => dir_get_home(),
+        };
+        dir_expand_path(dirstring, &basedir)
+    }
+
+    /// Replaces `$NAME` and `${NAME}` in `text` with environment variables.
+    /// `original` is the path as the caller gave it, for error messages.
+    fn dir_expand_vars(text: &str, original: &str) -> Result<String, LibFileError> {
+        let is_name_char = |ch: char| ch.is_ascii_alphanumeric() || ch == '_';
+        let mut expanded = String::new();
+        let mut rest = text;
+
+        while let Some(dollar) = rest.find('$') {
+            expanded.push_str(&rest[..dollar]);
+            let after = &rest[dollar + 1..];
+
+            // region Find the name and how much of the text after the `$` it takes up.
+            // Whatever it takes up is kept as it is if it isn't a name.
+            let (name, used) = match after.strip_prefix('{') {
+                Some(braced) => match braced.find('}') {
+                    Some(close) => (&braced[..close], close + 2),
+                    None => ("", 0),  // No closing brace:  keep the `$` as it is.
+                },
+                None if after.starts_with('$') => ("", 1),  // `$$` is kept as it is.
+                None => {
+                    let len = after.find(|ch: char| !is_name_char(ch)).unwrap_or(after.len());
+                    (&after[..len], len)
+                }
+            };
+            let is_name = name.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_')
+                && name.chars().all(is_name_char);
+            // endregion
+
+            match is_name {
+                false => {
+                    expanded.push('$');
+                    expanded.push_str(&after[..used]);
+                    rest = &after[used..];
+                }
+                true => match env::var(name) {
+                    Ok(value) => {
+                        expanded.push_str(&value);
+                        rest = &after[used..];
+                    }
+                    Err(_) => return Err(LibFileError::UnsetVariable {
+                        path: original.to_string(),
+                        name: name.to_string(),
+                    }),
+                },
+            }
+        }
+        expanded.push_str(rest);
+        Ok(expanded)
+    }
+
+    /// The home directory of user `user`.  On Unix it comes from
+    /// `/etc/passwd`; otherwise, and for users not listed there, it is
+    /// guessed as a sibling of the current user's home directory, if that
+    /// directory exists.
+    fn dir_user_home(user: &str) -> Option<String> {
+        let listed = match cfg!(unix) {
+            true => fs::read_to_string("/etc/passwd").ok().and_then(|passwd| {
+                passwd.lines()
+                    .map(|line| line.split(':').collect::<Vec<&str>>())
+                    .find(|fields| fields.len() > 5 && fields[0] == user)
+                    .map(|fields| fields[5].to_string())
+            }),
+            false => None,
+        };
+        listed.or_else(|| {
+            let sibling = Path::new(&dir_get_home()).parent()?.join(user);
+            match sibling.is_dir() {
+                true => Some(sibling.to_string_lossy().into_owned()),
+                false => None,
+            }
+        })
+    }
+
+    /// How far `dir_resolve` may stray from the path it is given.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum PathAccept {
+        /// Only an existing directory will do.
+        Dir,
+        /// An existing directory, or else the directory the path sits in.  That
+        /// covers an existing file, and a file name that doesn't exist yet.
+        FileParent,
+        /// An existing directory, or else the nearest existing directory above it.
+        Ancestor,
+    }
+
+    /// What `dir_resolve` falls back to when `PathAccept` doesn't find a directory.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum PathFallback {
+        /// No fallback:  the path is reported as not found.
+        None,
+        /// The user's home directory, from `dir_get_home()`.
+        Home,
+        /// A directory of the caller's choosing.  It has to exist.
+        Given(String),
+    }
+
+    /// Which rule produced a `ResolvedPath`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Resolution {
+        /// The path was an existing directory.  It is returned as
+        /// `dir_expand()` left it, so `~` and variables are filled in and a
+        /// relative path is made absolute.
+        AsGiven,
+        /// The directory the path sits in was used.
+        FileParent,
+        /// The nearest existing directory above the path was used.
+        Ancestor,
+        /// The home directory was used.
+        Home,
+        /// The caller's fallback directory was used.
+        Given,
+    }
+
+    /// A directory found by `dir_resolve`, and how it was found.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ResolvedPath {
+        pub path: String,
+        pub resolution: Resolution,
+    }
+
+    /// Turns a path into a directory that can be used, such as the start
+    /// directory of a dialog.
     ///
-    /// ### Case 4: Cannot Resolve the Input Path
-    /// - If none of the above conditions are met (e.g., the path is invalid), an error message will be printed
-    ///   to `stderr`, and it will return the user's home directory as a fallback.
+    /// `accept` says which directories near `dirstring` are acceptable, and
+    /// `fallback` says what to use when none of them is.  The result records
+    /// which rule applied, so the caller can tell the user when their path
+    /// was not used.  Empty paths, and paths with no parent (such as `/` when
+    /// it is missing), fall through to `fallback` rather than panicking.
     ///
-    /// #### Parameters
-    /// - `dirstring`: A string slice holding the directory or file path to normalize.
+    /// This replaces `dir_check_valid` and `dir_normalize_path`.  The old
+    /// `dir_normalize_path(path).unwrap_or_else(|_| dir_get_home())` is
+    /// `dir_resolve(path, PathAccept::FileParent, PathFallback::Home)`, or
+    /// `dir_resolve_or_home(path)` for short.  The path is expanded with
+    /// `dir_expand()` first, so `~/banks` and `$QBNK_HOME` work.
     ///
-    /// #### Returns
-    /// - A `String` containing the normalized path, based on the provided input's resolution.
+    /// # Errors
     ///
-    /// #### Panics
-    /// - The function uses `unwrap()` in scenarios where it is guaranteed that the operation is safe
-    ///   (e.g., when fetching the parent of a file path).
+    /// * `LibFileError::NotFound` if nothing is acceptable and `fallback` is
+    ///   `PathFallback::None`, or names a directory that doesn't exist.
     ///
-    /// #### Side Effects
-    /// - Prints an error message to `stderr` if the input cannot be resolved to a valid path or file.
+    /// # Example
     ///
-    /// #### Example
-    ///     fn main() {
-    ///         let newstring = dir_normalize_path("/home/jtreagan/programming/mine/cards");
-    ///         println!("\n The normalized path is: {} \n", newstring);
+    /// ```ignore
+    /// fn main() {
+    ///     match dir_resolve("/home/user/banks/algebra.bnk", PathAccept::FileParent, PathFallback::Home) {
+    ///         Ok(resolved) => println!("Using {} ({:?})", resolved.path, resolved.resolution),
+    ///         Err(err) => println!("{}", err),
     ///     }
+    ///     // Using /home/user/banks (FileParent)
+    /// }
+    /// ```
     ///
-    /// This example would print the home directory path if the given path `/some/invalid/file.txt`
-    /// cannot be resolved to a legitimate file or directory path.
-    ///
-    /// #### Notes
-    /// - The function relies on another method `dir_get_home` to fetch the user's home directory when
-    ///   the input path is invalid.
-    pub fn dir_normalize_path(dirstring: &str) -> String {
-        let usepath = Path::new(dirstring);
-
-        // region Case 1: The path does not need modification.
+    pub fn dir_resolve(dirstring: &str, accept: PathAccept, fallback: PathFallback)
+                                                    -> Result<ResolvedPath, LibFileError> {
+        let expanded = match (dir_expand(dirstring), &fallback) {
+            (Ok(expanded), _) => expanded,
+            (Err(err), PathFallback::None) => return Err(err),
+            (Err(_), _) => String::new(),  // Can't be expanded, so let the fallback handle it.
+        };
+        let usepath = Path::new(&expanded);
+        let found = |path: &Path, resolution: Resolution| {
+            Ok(ResolvedPath { path: path.to_string_lossy().into_owned(), resolution })
+        };
+
+        // region Try the path itself, then whatever `accept` allows above it.
         if usepath.is_dir() {
-            return usepath.to_string_lossy().into_owned();
+            return found(usepath, Resolution::AsGiven);
         }
+        let mut ancestors = usepath.ancestors().skip(1)
+            .filter(|dir| !dir.as_os_str().is_empty());  // `Path::new("notes").parent()` is "".
+        let accepted = match accept {
+            PathAccept::Dir => None,
+            PathAccept::FileParent => ancestors.next()
+                .filter(|parent| parent.is_dir())
+                .map(|parent| (parent, Resolution::FileParent)),
+            PathAccept::Ancestor => ancestors.find(|dir| dir.is_dir())
+                .map(|ancestor| (ancestor, Resolution::Ancestor)),
+        };
         // endregion
 
-        // region Case 2: The path points to an existing file.
-        if usepath.is_file() {
-            let newpath = usepath.parent();
-            if newpath.is_some() {
-                return newpath.unwrap().to_string_lossy().into_owned();
-                // The unwrap() is safe because the path is a file.
-            }
+        match (accepted, fallback) {
+            (Some((dir, resolution)), _) => found(dir, resolution),
+            (None, PathFallback::None) => Err(LibFileError::NotFound(dirstring.to_string())),
+            (None, PathFallback::Home) => Ok(ResolvedPath { path: dir_get_home(), resolution: Resolution::Home }),
+            (None, PathFallback::Given(dir)) => match Path::new(&dir).is_dir() {
+                true => Ok(ResolvedPath { path: dir, resolution: Resolution::Given }),
+                false => Err(LibFileError::NotFound(dir)),
+            },
         }
-        // endregion
+    }
 
-        // region Case 3: The string could be a directory with an inaccurate file name on the end.
-        let newpath = usepath.parent().unwrap();
-        if newpath.is_dir() {
-            return newpath.to_string_lossy().into_owned();
+    /// The directory to start in for a path the user or a caller suggested:
+    /// the path itself if it is a directory, else the directory it sits in,
+    /// else the home directory.  This is what the dialogs and widgets use.
+    pub fn dir_resolve_or_home(dirstring: &str) -> String {
+        match dir_resolve(dirstring, PathAccept::FileParent, PathFallback::Home) {
+            Ok(resolved) => resolved.path,
+            Err(_) => dir_get_home(),  // Can't happen with the home fallback.
         }
-        // endregion
-
-        // region Case 4: The path cannot be resolved so default to the home directory.
-        eprintln!("\n Error!  The passed string,   '{}'   is not a path or file.", dirstring);
-        dir_get_home()
-        // endregion
     }
 
 
@@ -784,39 +3739,42 @@ pub mod dir_mngmnt {
 
 
     /// Check the validity of a directory path and correct it if necessary.
+    /// The user is asked for a corrected path until one exists.  Entering a
+    /// blank path gives up and returns `LibFileError::Cancelled`.  A path
+    /// that can't be expanded (see `dir_expand_path`) returns the error.
     ///
     /// Example:
     ///
     ///     fn main() {
-    ///         let mut dirpath: String = "kjhkjhjkh/home/camascounty/programming/rust/mine/file_lib".to_string();
-    ///
-    ///         let dirchecked = dir_checkexist_fix(&dirpath);
-    ///         if dirchecked.0 == false {
-    ///             println!("\n The path \n      {} \n was not usable and was not corrected. \n", dirpath);
-    ///         } else {
-    ///            println!("\n The correct path is:  {}", dirchecked.1);
-    ///            println!("\n All is okay!!  :>) \n");
+    ///         let dirpath: String = "kjhkjhjkh/home/camascounty/programming/rust/mine/file_lib".to_string();
+    ///
+    ///         match dir_checkexist_fix(&dirpath) {
+    ///             Ok(corrected) => {
+    ///                 println!("\n The correct path is:  {}", corrected);
+    ///                 println!("\n All is okay!!  :>) \n");
+    ///             }
+    ///             Err(err) => {
+    ///                 println!("\n The path \n      {} \n was not usable:  {} \n", dirpath, err);
+    ///             }
     ///         }
     ///     }
-    pub fn dir_checkexist_fix(dirpath: &String) -> (bool, String) {
-        let mut fullpath = Path::new(dirpath.as_str());
-        let mut newpath: String = dirpath.to_string().clone();
+    pub fn dir_checkexist_fix(dirpath: &String) -> Result<String, LibFileError> {
+        let mut newpath: String = dirpath.to_string();
 
         loop {
-            let exists = fullpath.try_exists()
-                .expect("Error when checking the existence of the directory");
+            let expanded = dir_expand(&newpath)?;
+            let exists = Path::new(&expanded).try_exists()
+                .map_err(|err| LibFileError::from_io(err, &expanded))?;
 
             if exists {
-                return (true, newpath);
+                return Ok(expanded);
             } else {
                 println!("\n The directory \n      {} \n does not exist and may not be used.", newpath);
                 newpath = input_string_prompt(
                     "\n Please enter a corrected path for the directory in which you wish to save this file.  \n\
                          (Do not include the file name):   ");  // Eventually add ability to edit the existing string.
-                if newpath == "" {
-                    return (false, "".to_string());
-                } else {
-                    fullpath = Path::new(newpath.as_str());
+                if newpath.is_empty() {
+                    return Err(LibFileError::Cancelled);
                 }
             }
         }
@@ -842,67 +3800,354 @@ pub mod dir_mngmnt {
     ///     }
     ///     }
     ///
-    pub fn dir_check_empty(dirpath: &str) -> io::Result<bool> {
-        let mut entries = fs::read_dir(dirpath)?;
+    pub fn dir_check_empty(dirpath: &str) -> Result<bool, LibFileError> {
+        let mut entries = fs::read_dir(dir_expand(dirpath)?)
+            .map_err(|err| LibFileError::from_io(err, dirpath))?;
         let first_entry = entries.next();
         Ok(first_entry.is_none())
     }
 
-    /// Check a user-entered path for validity.
+    /// Check a user-entered path for validity.  Returns the path, corrected
+    /// by the user if necessary.  Returns `LibFileError::Cancelled` if the
+    /// user gave up on correcting it.
+    ///
+    /// Example:
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     match dir_get_path() {
+    ///         Ok(dirpath) => println!("\n You chose:  {} \n", dirpath),
+    ///         Err(err) => println!("\n No directory was chosen:  {} \n", err),
+    ///     }
+    /// }
+    /// ```
     ///
-    pub fn dir_get_path() -> (bool, String) {
-        let dirpath = input_string_prompt(
+    pub fn dir_get_path() -> Result<String, LibFileError> {
+        dir_get_path_prompt(
             "\n Please enter a path for the directory in which you wish to save this file.  \n\
-              (Do not include the file name):   ");
-
-        let dirok = dir_checkexist_fix(&dirpath);
-        if dirok.0 == false {
-            println!("\n The path \n   {} \n was not usable and was not corrected. \n", dirpath);
-            panic!("Invalid and uncorrected path entered.");
-            // todo: Maybe eventually return an error that the main program can use to
-            // redirect the user's activity.
-        }
-        (true, dirpath)
+              (Do not include the file name):   ")
     }
 
     /// Same as `dir_get_path` except that one can pass whatever prompt
     /// you like to the function.
-    pub fn dir_get_path_prompt(prompt: &str) -> (bool, String) {
+    pub fn dir_get_path_prompt(prompt: &str) -> Result<String, LibFileError> {
         let dirpath = input_string_prompt( prompt);
-        let dirok = dir_checkexist_fix(&dirpath);
-        if dirok.0 == false {
-            println!("\n The path \n   {} \n was not usable and was not corrected. \n", dirpath);
-            panic!("Invalid and uncorrected path entered.");
-            // todo: Maybe eventually return an error that the main program can use to
-            // redirect user's activity.
+        dir_checkexist_fix(&dirpath)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::process;
+
+        /// A fresh scratch directory for one test.
+        fn scratch_dir(name: &str) -> PathBuf {
+            let dir = env::temp_dir().join(format!("lib_file_dir_{}_{}", name, process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).expect("The scratch directory can be made.");
+            dir
+        }
+
+        #[test]
+        fn last_dirs_survive_a_save_and_load() {
+            let dir = scratch_dir("lastdirs");
+            let storepath = dir.join("state/qbnk/lastdirs").to_string_lossy().into_owned();
+
+            let mut lastdirs = LastDirs::load(&storepath).expect("A missing store loads empty.");
+            assert_eq!(lastdirs.get("lists"), None);
+            lastdirs.set("lists", "/home/user/lists").expect("The purpose and directory are usable.");
+            lastdirs.set("banks", "/home/user/my banks").expect("The purpose and directory are usable.");
+            lastdirs.set("lists", "/home/user/newer lists").expect("The purpose and directory are usable.");
+            lastdirs.save().expect("The store and its directories can be written.");
+
+            let reloaded = LastDirs::load(&storepath).expect("The saved store can be read.");
+            assert_eq!(reloaded.get("lists"), Some("/home/user/newer lists"));
+            assert_eq!(reloaded.get("banks"), Some("/home/user/my banks"));
+            assert_eq!(reloaded.store_path(), storepath);
+            let _ = fs::remove_dir_all(&dir);
+        }
+
+        #[test]
+        fn last_dirs_reject_tabs_and_skip_broken_lines() {
+            let dir = scratch_dir("lastdirstabs");
+            let storepath = dir.join("lastdirs").to_string_lossy().into_owned();
+            fs::write(&storepath, "lists\t/home/user/lists\nno tab here\n\t/no/purpose\nbanks\t\n")
+                .expect("The scratch store can be written.");
+
+            let mut lastdirs = LastDirs::load(&storepath).expect("The scratch store can be read.");
+            assert_eq!(lastdirs.get("lists"), Some("/home/user/lists"));
+            assert_eq!(lastdirs.get("banks"), None);
+
+            assert!(matches!(lastdirs.set("my\tlists", "/home/user"), Err(LibFileError::InvalidPath(_))));
+            assert!(matches!(lastdirs.set("lists", "/home/user\t/x"), Err(LibFileError::InvalidPath(_))));
+            assert!(matches!(lastdirs.set("lists", "/home/user\n/x"), Err(LibFileError::InvalidPath(_))));
+            assert_eq!(lastdirs.get("lists"), Some("/home/user/lists"));  // Unchanged by the failed calls.
+            let _ = fs::remove_dir_all(&dir);
+        }
+
+        #[test]
+        fn resolve_keeps_an_existing_directory_expanded() {
+            let root = dir_resolve("/", PathAccept::Dir, PathFallback::None).expect("The root exists.");
+            assert_eq!(root, ResolvedPath { path: "/".to_string(), resolution: Resolution::AsGiven });
+
+            let cwd = env::current_dir().expect("The tests have a working directory.");
+            let here = dir_resolve(".", PathAccept::Dir, PathFallback::None).expect("The working directory exists.");
+            assert_eq!(Path::new(&here.path).canonicalize().ok(), cwd.canonicalize().ok());
+            assert!(Path::new(&here.path).is_absolute());
+        }
+
+        #[test]
+        fn resolve_sends_empty_paths_to_the_fallback() {
+            let home = dir_resolve("", PathAccept::FileParent, PathFallback::Home).expect("Home is the fallback.");
+            assert_eq!(home, ResolvedPath { path: dir_get_home(), resolution: Resolution::Home });
+            assert!(matches!(dir_resolve("", PathAccept::Ancestor, PathFallback::None), Err(LibFileError::NotFound(_))));
+        }
+
+        #[test]
+        fn resolve_accepts_what_each_policy_allows() {
+            let dir = scratch_dir("resolve");
+            fs::write(dir.join("algebra.bnk"), "").expect("The scratch file can be written.");
+            let dirstr = dir.to_string_lossy().into_owned();
+            let inside = |rest: &str| dir.join(rest).to_string_lossy().into_owned();
+
+            let resolved = dir_resolve(&inside("algebra.bnk"), PathAccept::FileParent, PathFallback::None);
+            assert_eq!(resolved.ok(), Some(ResolvedPath { path: dirstr.clone(), resolution: Resolution::FileParent }));
+            let resolved = dir_resolve(&inside("new.bnk"), PathAccept::FileParent, PathFallback::None);
+            assert_eq!(resolved.ok(), Some(ResolvedPath { path: dirstr.clone(), resolution: Resolution::FileParent }));
+            assert!(dir_resolve(&inside("a/b/c"), PathAccept::FileParent, PathFallback::None).is_err());
+
+            let resolved = dir_resolve(&inside("a/b/c"), PathAccept::Ancestor, PathFallback::None);
+            assert_eq!(resolved.ok(), Some(ResolvedPath { path: dirstr.clone(), resolution: Resolution::Ancestor }));
+
+            let resolved = dir_resolve(&inside("algebra.bnk"), PathAccept::Dir, PathFallback::Given(dirstr.clone()));
+            assert_eq!(resolved.ok(), Some(ResolvedPath { path: dirstr.clone(), resolution: Resolution::Given }));
+            let missing = PathFallback::Given(inside("nothere"));
+            assert!(matches!(dir_resolve(&inside("algebra.bnk"), PathAccept::Dir, missing),
+                             Err(LibFileError::NotFound(path)) if path == inside("nothere")));
+
+            assert!(matches!(dir_resolve(&inside("algebra.bnk"), PathAccept::Dir, PathFallback::None),
+                             Err(LibFileError::NotFound(_))));
+            let _ = fs::remove_dir_all(&dir);
+        }
+
+        #[cfg(all(unix, not(target_os = "macos")))]
+        #[test]
+        fn xdg_variables_override_the_base_dirs_only_when_absolute() {
+            let home = dir_get_home();
+            let cases = [
+                ("XDG_CONFIG_HOME", dir_get_config as fn(&str) -> String, ".config"),
+                ("XDG_DATA_HOME", dir_get_data, ".local/share"),
+                ("XDG_CACHE_HOME", dir_get_cache, ".cache"),
+                ("XDG_STATE_HOME", dir_get_state, ".local/state"),
+            ];
+
+            for (var, get_base, fallback) in cases {
+                let saved = env::var_os(var);
+                let default = Path::new(&home).join(fallback).join("qbnk").to_string_lossy().into_owned();
+
+                // SAFETY:  no other test reads or writes the XDG variables.
+                unsafe { env::set_var(var, "/srv/xdg") };
+                assert_eq!(get_base("qbnk"), "/srv/xdg/qbnk", "{}", var);
+                unsafe { env::set_var(var, "relative/xdg") };
+                assert_eq!(get_base("qbnk"), default, "{}", var);  // The spec says to ignore relative paths.
+                unsafe { env::remove_var(var) };
+                assert_eq!(get_base("qbnk"), default, "{}", var);
+
+                if let Some(value) = saved {
+                    unsafe { env::set_var(var, value) };
+                }
+            }
+        }
+
+        #[test]
+        fn set_variables_are_expanded() {
+            let path = env::var("PATH").expect("PATH is set for the tests.");
+            assert_eq!(dir_expand_vars("$PATH/x", "$PATH/x").ok(), Some(format!("{}/x", path)));
+            assert_eq!(dir_expand_vars("a${PATH}b", "a${PATH}b").ok(), Some(format!("a{}b", path)));
+        }
+
+        #[test]
+        fn dollars_without_a_name_are_kept() {
+            for text in ["$5/lists", "a$$b", "cost$", "${5}", "${}", "${PATH", "$-x", "$$PATH"] {
+                assert_eq!(dir_expand_vars(text, text).ok().as_deref(), Some(text));
+            }
+        }
+
+        #[test]
+        fn unset_variables_are_named_in_the_error() {
+            let text = "/x/${LIB_FILE_NOT_SET_ANYWHERE}/y";
+            match dir_expand_vars(text, text) {
+                Err(LibFileError::UnsetVariable { path, name }) => {
+                    assert_eq!(path, text);
+                    assert_eq!(name, "LIB_FILE_NOT_SET_ANYWHERE");
+                }
+                other => panic!("Expected an unset variable, got {:?}", other),
+            }
         }
-        (true, dirpath)
     }
 
 
 
+} // End of dir_mngmnt module.
+
+/// # Terminal-based file i/o functions.
+///
+pub mod file_mngmnt {
+
+//! ### Note the following:
+//! 1) I wrote these functions early-on while I was still
+//!     learning Rust and the code quality reflects that.
+//! 2) While the previous module -- file_fltk -- is
+//!     dependent on the FLTK-RS crate, the functions in
+//!     this module rely on the Rust standard crates along with
+//!     some functions from `lib_utils`, another of my personal
+//!     crates.  These functions are all terminal-based.
+
+    use lib_utils::{input_utilities::*, utilities::*};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::{collections::HashSet, fmt::Debug, fs, fs::File, path::{Path, PathBuf}, str::FromStr};
+    use std::{cmp::Ordering, iter::Peekable, str::Chars, time::SystemTime};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::dir_mngmnt::{dir_check_empty, dir_checkexist_fix, dir_expand, dir_get_state};
+    use crate::file_errors::LibFileError;
+
+    /// A most-recently-used list of files, newest first, kept in a small
+    /// text file between runs.  Each application keeps its own list.
+    ///
+    /// The list never holds more than its maximum length, never holds the
+    /// same path twice, and drops files that no longer exist when it is
+    /// loaded.  The file holds one path per line.
+    ///
+    /// `file_fltk::fltk_fill_recent_menu()` shows the list in an FLTK menu.
+    ///
+    /// Example:
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let mut recent = match RecentFiles::load(&RecentFiles::default_path("qbnk"), 8) {
+    ///         Ok(recent) => recent,
+    ///         Err(err) => {
+    ///             println!("\n {} \n", err);
+    ///             return;
+    ///         }
+    ///     };
+    ///
+    ///     match recent.add("/home/somebody/lists/algebra.lst").and_then(|_| recent.save()) {
+    ///         Ok(()) => println!("\n Recent files:  {:?} \n", recent.paths()),
+    ///         Err(err) => println!("\n {} \n", err),
+    ///     }
+    /// }
+    /// ```
+    ///
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct RecentFiles {
+        storepath: String,
+        maxlen: usize,
+        paths: Vec<String>,
+    }
+
+    impl RecentFiles {
+        /// Reads the list at `storepath`, keeping at most `maxlen` entries.
+        /// A missing file gives an empty list, which is written there by
+        /// the first `save()`.
+        pub fn load(storepath: &str, maxlen: usize) -> Result<RecentFiles, LibFileError> {
+            let contents = match fs::read_to_string(storepath) {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(err) => return Err(LibFileError::from_io(err, storepath)),
+            };
+
+            let mut recent = RecentFiles { storepath: storepath.to_string(), maxlen, paths: Vec::new() };
+            for line in contents.lines().filter(|line| !line.is_empty()) {
+                if !recent.paths.iter().any(|path| path == line) {
+                    recent.paths.push(line.to_string());
+                }
+            }
+            recent.prune();
+            Ok(recent)
+        }
+
+        /// The usual place to keep an application's list:  `recent` in the
+        /// application's state directory (see `dir_get_state()`), such as
+        /// `<home>/.local/state/<appname>/recent`.
+        pub fn default_path(appname: &str) -> String {
+            Path::new(&dir_get_state(appname)).join("recent")
+                .to_string_lossy().into_owned()
+        }
+
+        /// The file this list is read from and saved to.
+        pub fn store_path(&self) -> &str {
+            &self.storepath
+        }
+
+        /// The paths in the list, newest first.
+        pub fn paths(&self) -> &[String] {
+            &self.paths
+        }
+
+        /// The most entries the list keeps.
+        pub fn max_len(&self) -> usize {
+            self.maxlen
+        }
+
+        /// Changes the most entries the list keeps, dropping the oldest
+        /// entries if the list is now too long.
+        pub fn set_max_len(&mut self, maxlen: usize) {
+            self.maxlen = maxlen;
+            self.paths.truncate(maxlen);
+        }
+
+        /// Puts `path` at the top of the list.  If it was already in the list,
+        /// it moves up rather than appearing twice.  A path holding a line
+        /// break can't be stored and returns `LibFileError::InvalidPath`.
+        pub fn add(&mut self, path: &str) -> Result<(), LibFileError> {
+            if path.is_empty() || path.contains(['\n', '\r']) {
+                return Err(LibFileError::InvalidPath(path.to_string()));
+            }
+            self.paths.retain(|existing| existing != path);
+            self.paths.insert(0, path.to_string());
+            self.paths.truncate(self.maxlen);
+            Ok(())
+        }
+
+        /// Takes `path` out of the list.
+        pub fn remove(&mut self, path: &str) {
+            self.paths.retain(|existing| existing != path);
+        }
 
-} // End of dir_mngmnt module.
+        /// Empties the list.
+        pub fn clear(&mut self) {
+            self.paths.clear();
+        }
 
-/// # Terminal-based file i/o functions.
-///
-pub mod file_mngmnt {
+        /// Drops the files that no longer exist, and any entries past the
+        /// maximum length.
+        pub fn prune(&mut self) {
+            self.paths.retain(|path| Path::new(path).is_file());
+            self.paths.truncate(self.maxlen);
+        }
 
-//! ### Note the following:
-//! 1) I wrote these functions early-on while I was still
-//!     learning Rust and the code quality reflects that.
-//! 2) While the previous module -- file_fltk -- is
-//!     dependent on the FLTK-RS crate, the functions in
-//!     this module rely on the Rust standard crates along with
-//!     some functions from `lib_utils`, another of my personal
-//!     crates.  These functions are all terminal-based.
+        /// Writes the list back to its file, creating the file's directory
+        /// if needed.
+        pub fn save(&self) -> Result<(), LibFileError> {
+            match Path::new(&self.storepath).parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => {
+                    fs::create_dir_all(parent)
+                        .map_err(|err| LibFileError::from_io(err, &parent.to_string_lossy()))?;
+                }
+                _ => {},
+            }
 
-    use lib_utils::{input_utilities::*, utilities::*};
-    use std::io::{BufRead, BufReader, Read, Write};
-    use std::{fmt::Debug, fs, fs::File, io, path::Path, str::FromStr};
-    use std::cell::RefCell;
-    use std::rc::Rc;
-    use crate::dir_mngmnt::{dir_check_empty, dir_checkexist_fix};
+            let mut contents = String::new();
+            for path in &self.paths {
+                contents.push_str(path);
+                contents.push('\n');
+            }
+            fs::write(&self.storepath, contents)
+                .map_err(|err| LibFileError::from_io(err, &self.storepath))
+        }
+    }
 
     /// Checks the file extension of a given filename.
     ///
@@ -949,6 +4194,125 @@ pub mod file_mngmnt {
         }
     }
 
+    /// Checks whether a file name matches a shell-style pattern.
+    ///
+    /// The pattern can hold:
+    /// * `*` for any run of characters (including none),
+    /// * `?` for any one character,
+    /// * `[abc]`, `[a-z]` or `[!abc]` for one character from (or not from) a set,
+    /// * `{lst,vrbl}` for any one of several alternatives.
+    ///
+    /// Everything else must match exactly, so the comparison is case-sensitive.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     for name in ["algebra.lst", "algebra.vrbl", "algebra.bnk"] {
+    ///         let keep = file_glob_match("*.{lst,vrbl}", name);
+    ///         println!("{:15} -> {}", name, keep);
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn file_glob_match(pattern: &str, name: &str) -> bool {
+        match file_glob_braces(pattern) {
+            Some(alternatives) => alternatives.iter().any(|alt| file_glob_match(alt, name)),
+            None => {
+                let patternchars: Vec<char> = pattern.chars().collect();
+                let namechars: Vec<char> = name.chars().collect();
+                file_glob_chars(&patternchars, &namechars)
+            }
+        }
+    }
+
+    /// Expands the first `{a,b}` group of a pattern into one pattern per
+    /// alternative.  Returns `None` if the pattern has no complete group.
+    fn file_glob_braces(pattern: &str) -> Option<Vec<String>> {
+        let open = pattern.find('{')?;
+
+        // region Find the matching `}` and the commas that belong to this group.
+        let mut depth = 0;
+        let mut close = None;
+        let mut commas = Vec::new();
+        for (index, ch) in pattern[open..].char_indices() {
+            let index = index + open;
+            match ch {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = Some(index);
+                        break;
+                    }
+                }
+                ',' if depth == 1 => commas.push(index),
+                _ => {},
+            }
+        }
+        let close = close?;
+        // endregion
+
+        let prefix = &pattern[..open];
+        let suffix = &pattern[close + 1..];
+        let mut alternatives = Vec::new();
+        let mut start = open + 1;
+        commas.push(close);
+        for end in commas {
+            alternatives.push(format!("{}{}{}", prefix, &pattern[start..end], suffix));
+            start = end + 1;
+        }
+        Some(alternatives)
+    }
+
+    /// Matches a brace-free pattern against a name, one character at a time.
+    fn file_glob_chars(pattern: &[char], name: &[char]) -> bool {
+        match pattern.first() {
+            None => name.is_empty(),
+            Some('*') => (0..=name.len()).any(|skip| file_glob_chars(&pattern[1..], &name[skip..])),
+            Some('?') => !name.is_empty() && file_glob_chars(&pattern[1..], &name[1..]),
+            Some('[') => {
+                match name.first() {
+                    None => false,
+                    Some(&ch) => match file_glob_class(pattern, ch) {
+                        Some((true, used)) => file_glob_chars(&pattern[used..], &name[1..]),
+                        Some((false, _)) => false,
+                        None => ch == '[' && file_glob_chars(&pattern[1..], &name[1..]),  // No `]`, so `[` is literal.
+                    },
+                }
+            }
+            Some(pch) => name.first() == Some(pch) && file_glob_chars(&pattern[1..], &name[1..]),
+        }
+    }
+
+    /// Checks one character against the `[...]` set at the start of `pattern`.
+    /// Returns whether it matched and how many pattern characters the set used,
+    /// or `None` if the set is never closed.
+    fn file_glob_class(pattern: &[char], ch: char) -> Option<(bool, usize)> {
+        let negate = matches!(pattern.get(1), Some('!') | Some('^'));
+        let start = if negate { 2 } else { 1 };
+        let mut index = start;
+        let mut found = false;
+
+        while index < pattern.len() {
+            if pattern[index] == ']' && index > start {
+                return Some((found != negate, index + 1));
+            }
+            if index + 2 < pattern.len() && pattern[index + 1] == '-' && pattern[index + 2] != ']' {
+                if pattern[index] <= ch && ch <= pattern[index + 2] {
+                    found = true;
+                }
+                index += 3;
+            } else {
+                if pattern[index] == ch {
+                    found = true;
+                }
+                index += 1;
+            }
+        }
+        None
+    }
+
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // More recently written functions are above.  Older functions that may need modification are below.
@@ -957,23 +4321,18 @@ pub mod file_mngmnt {
 
     /// Read a file to a String and print that String to the terminal.
     ///
-    pub fn file_read_print_to_term(fname: String) {
-        let mut file = File::open(fname.as_str()).expect("Can't open file!");
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).expect("Oops!  Cant read file...");
+    pub fn file_read_print_to_term(fname: String) -> Result<(), LibFileError> {
+        let contents = file_read_to_string(fname.as_str())?;
 
         println!("{}", contents);
+        Ok(())
     }
 
     /// Read a file to a String with the file name passed
     /// to the function as a RefCell.
-    pub fn file_read_file_to_string_refcell(fname: &Rc<RefCell<String>>) -> String {
+    pub fn file_read_file_to_string_refcell(fname: &Rc<RefCell<String>>) -> Result<String, LibFileError> {
         let usefname = fname.borrow().clone();
-
-        let mut file = File::open(usefname.as_str()).expect("Can't open file!");
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).expect("Oops!  Cant read file...");
-        contents
+        file_read_to_string(usefname.as_str())
     }
 
     /// Read a comma delimited file and collect its contents into a vector.
@@ -985,18 +4344,19 @@ pub mod file_mngmnt {
     ///     // a *.csv (comma separated values) file.
     ///
     ///         let file_path = "/home/somebody/somewhere/rusty/nails.csv";
-    ///         let vec = file_read_csv_to_vector(file_path);
-    ///
-    ///         println!("\n {:?} \n", vec); // Print the resulting vector
+    ///         match file_read_csv_to_vector(file_path) {
+    ///             Ok(vec) => println!("\n {:?} \n", vec), // Print the resulting vector
+    ///             Err(err) => println!("\n {} \n", err),
+    ///         }
     ///     }
-    pub fn file_read_csv_to_vector(file_path: &str) -> Vec<String> {  // Comma delimited
+    pub fn file_read_csv_to_vector(file_path: &str) -> Result<Vec<String>, LibFileError> {  // Comma delimited
         // Read the file into a string
-        let content = fs::read_to_string(file_path).expect("Failed to read the file");
+        let content = file_read_to_string(file_path)?;
 
         // Split the content by commas and collect into a vector
-        content.split(',')
+        Ok(content.split(',')
             .map(|s| s.trim().to_string()) // Trim whitespace off each element
-            .collect()
+            .collect())
     }
 
     /// Read a file's contents into a String and return
@@ -1017,60 +4377,485 @@ pub mod file_mngmnt {
     ///     }
     ///     }
     ///
-    pub fn file_read_to_string(fname: &str) -> io::Result<String> {
-        // TODO: This needs better error handling.
-
+    pub fn file_read_to_string(fname: &str) -> Result<String, LibFileError> {
         // Attempt to open the file
-        let mut file = File::open(fname)?;
+        let mut file = File::open(fname)
+            .map_err(|err| LibFileError::from_io(err, fname))?;
 
         // Prepare a String to store the file's contents
         let mut contents = String::new();
 
-        // Read the file's contents into the string
-        file.read_to_string(&mut contents)?;
+        // Read the file's contents into the string.  A file that is not
+        //      valid UTF-8 shows up here as `LibFileError::InvalidUtf8`.
+        file.read_to_string(&mut contents)
+            .map_err(|err| LibFileError::from_io(err, fname))?;
 
         // Return the contents
         Ok(contents)
     }
 
     /// Pull the file name off of the end of a path and return it.
+    /// Returns `LibFileError::InvalidPath` if the path has no file name
+    /// on the end (e.g. `/` or `..`).
     ///
-    pub fn file_path_to_fname(pathstr: &String) -> String {
-        // todo: This doesn't look right.  Check it out.
+    pub fn file_path_to_fname(pathstr: &String) -> Result<String, LibFileError> {
         let usepath = Path::new(pathstr);
 
         match usepath.file_name() {
             Some(filename_osstr) => {
                 match filename_osstr.to_str() {
-                    Some(filename_str) => filename_str.to_string(),
-                    None => "Error: Could not convert filename to UTF-8 string.".to_string(),
+                    Some(filename_str) => Ok(filename_str.to_string()),
+                    None => Err(LibFileError::InvalidUtf8(pathstr.to_string())),
                 }
             }
-            None => "Error: could not get filename".to_string()
+            None => Err(LibFileError::InvalidPath(pathstr.to_string())),
         }
     }
 
     /// Read a folder directory and collect the filenames into a vector.
-    /// Then return the vector.
+    /// Then return the vector.  Entries that can't be read, and names that
+    /// aren't valid UTF-8, are skipped so one odd file doesn't hide the rest.
     ///
     ///             ******* Example for file_get_dir_list() ******
     ///
     ///     fn main() {
     ///         let dirpath = "../qbnk_list";
-    ///         let file_names = file_get_dir_list(dirpath);
+    ///         match file_get_dir_list(dirpath) {
+    ///             Ok(file_names) => println!("\n In main() the list of files is \n {:?}", file_names),
+    ///             Err(err) => println!("\n Could not read the directory:  {}", err),
+    ///         }
+    ///     }
+    pub fn file_get_dir_list(path: &str) -> Result<Vec<String>, LibFileError> {
+        let dir_entries = fs::read_dir(dir_expand(path)?)
+            .map_err(|err| LibFileError::from_io(err, path))?;
+
+        let mut file_names: Vec<String> = Vec::new();
+        for entry in dir_entries.flatten() {
+            let isfile = match entry.file_type() {
+                Ok(filetype) => filetype.is_file(),
+                Err(_) => false,
+            };
+            if !isfile {
+                continue;
+            }
+            match entry.file_name().into_string() {
+                Ok(fname) => file_names.push(fname),
+                Err(_) => continue,
+            }
+        }
+
+        Ok(file_names)
+    }
+
+    /// Walks a directory and everything below it, collecting the files that
+    /// pass its filters.
+    ///
+    /// Start with `DirWalker::new()`, chain the settings you care about, and
+    /// finish with `walk()`.  By default the walk has no depth limit, no
+    /// patterns and no extension filter, skips hidden entries (names starting
+    /// with `.`), and skips symbolic links.
+    ///
+    /// Patterns are matched with `file_glob_match()` against both the entry's
+    /// name and its path relative to the root, so `*.bak` and `drafts/*` both
+    /// work.  Excluded directories are not entered.
+    ///
+    /// # Example
     ///
-    ///         println!("\n In main() the list of files is \n {:?}", file_names);
+    /// ```ignore
+    /// fn main() {
+    ///     let walked = DirWalker::new("~/qbnk_data")
+    ///         .max_depth(3)
+    ///         .extensions(&["lst", "bnk"])
+    ///         .exclude("old_*")
+    ///         .walk();
+    ///
+    ///     match walked {
+    ///         Ok(walked) => {
+    ///             for path in &walked.files {
+    ///                 println!("{}", path);
+    ///             }
+    ///             for err in &walked.errors {
+    ///                 println!("Skipped:  {}", err);
+    ///             }
+    ///         }
+    ///         Err(err) => println!("Could not read the directory:  {}", err),
     ///     }
-    pub fn file_get_dir_list(path: &str) -> Vec<String> {
-        let dir_entries = fs::read_dir(path).unwrap();
+    /// }
+    /// ```
+    ///
+    #[derive(Debug, Clone)]
+    pub struct DirWalker {
+        rootdir: String,
+        maxdepth: Option<usize>,
+        includes: Vec<String>,
+        excludes: Vec<String>,
+        extensions: Vec<String>,
+        hidden: bool,
+        followlinks: bool,
+    }
 
-        let file_names: Vec<String> = dir_entries
-            .filter_map(Result::ok)
-            .filter(|entry| entry.file_type().unwrap().is_file())
-            .map(|entry| entry.file_name().into_string().unwrap())
-            .collect();
+    /// What `DirWalker::walk()` found:  the files that passed, in walk order
+    /// (each directory's entries sorted by name, depth first), and the entries
+    /// that couldn't be read.
+    #[derive(Debug, Default)]
+    pub struct WalkResult {
+        pub files: Vec<String>,
+        pub errors: Vec<LibFileError>,
+    }
+
+    impl DirWalker {
+        /// Creates a walker for `rootdir`, with every setting at its default.
+        /// `rootdir` is expanded with `dir_expand()` when the walk starts.
+        pub fn new(rootdir: &str) -> DirWalker {
+            DirWalker {
+                rootdir: rootdir.to_string(),
+                maxdepth: None,
+                includes: Vec::new(),
+                excludes: Vec::new(),
+                extensions: Vec::new(),
+                hidden: false,
+                followlinks: false,
+            }
+        }
+
+        /// Limits how deep the walk goes.  Depth 1 is the files directly in the
+        /// root, the same as `file_get_dir_list()`; depth 2 adds the files one
+        /// directory down, and so on.  Depth 0 finds nothing.
+        pub fn max_depth(mut self, depth: usize) -> DirWalker {
+            self.maxdepth = Some(depth);
+            self
+        }
+
+        /// Keeps only files matching `pattern`.  Several calls keep files that
+        /// match any of the patterns.
+        pub fn include(mut self, pattern: &str) -> DirWalker {
+            self.includes.push(pattern.to_string());
+            self
+        }
+
+        /// Leaves out files and directories matching `pattern`.  Exclusions win
+        /// over inclusions.
+        pub fn exclude(mut self, pattern: &str) -> DirWalker {
+            self.excludes.push(pattern.to_string());
+            self
+        }
+
+        /// Keeps only files with one of these extensions, given without the
+        /// dot.  Case is ignored.  The extension is found with
+        /// `file_check_extension()`.
+        pub fn extensions(mut self, extnsns: &[&str]) -> DirWalker {
+            self.extensions = extnsns.iter().map(|extnsn| extnsn.trim_start_matches('.').to_string()).collect();
+            self
+        }
+
+        /// Includes hidden files and directories (names starting with `.`).
+        /// Off by default.
+        pub fn hidden(mut self, hidden: bool) -> DirWalker {
+            self.hidden = hidden;
+            self
+        }
+
+        /// Follows symbolic links to files and directories instead of skipping
+        /// them.  Directories already visited (through a link loop, say) are
+        /// not walked again.  A broken link is reported in `errors`.  Off by
+        /// default.
+        pub fn follow_symlinks(mut self, follow: bool) -> DirWalker {
+            self.followlinks = follow;
+            self
+        }
+
+        /// Walks the tree.  Entries that can't be read don't stop the walk;
+        /// they are collected in `WalkResult::errors`.
+        ///
+        /// # Errors
+        ///
+        /// * Whatever `dir_expand()` reports for the root.
+        /// * The error from reading the root itself, which is returned instead
+        ///   of an empty walk.
+        pub fn walk(&self) -> Result<WalkResult, LibFileError> {
+            let rootdir = dir_expand(&self.rootdir)?;
+            let rootpath = Path::new(&rootdir);
+            let mut walked = WalkResult::default();
+            let mut visited = HashSet::new();
+
+            fs::read_dir(rootpath).map_err(|err| LibFileError::from_io(err, &rootdir))?;
+            visited.insert(fs::canonicalize(rootpath).map_err(|err| LibFileError::from_io(err, &rootdir))?);
+
+            self.walk_dir(rootpath, rootpath, 1, &mut visited, &mut walked);
+            Ok(walked)
+        }
+
+        /// Walks one directory, whose entries are at `depth`, and recurses into
+        /// its subdirectories.
+        fn walk_dir(&self, rootpath: &Path, dir: &Path, depth: usize, visited: &mut HashSet<PathBuf>,
+                    walked: &mut WalkResult) {
+            if self.maxdepth.is_some_and(|maxdepth| depth > maxdepth) {
+                return;
+            }
+            let dirstr = dir.to_string_lossy().into_owned();
+
+            // region Read the directory's entries and sort them by name.
+            let dir_entries = match fs::read_dir(dir) {
+                Ok(dir_entries) => dir_entries,
+                Err(err) => {
+                    walked.errors.push(LibFileError::from_io(err, &dirstr));
+                    return;
+                }
+            };
+            let mut entries = Vec::new();
+            for entry in dir_entries {
+                match entry {
+                    Ok(entry) => entries.push(entry),
+                    Err(err) => walked.errors.push(LibFileError::from_io(err, &dirstr)),
+                }
+            }
+            entries.sort_by_key(|entry| entry.file_name());
+            // endregion
+
+            for entry in entries {
+                let entrypath = entry.path();
+                let entrystr = entrypath.to_string_lossy().into_owned();
+                let name = match entry.file_name().into_string() {
+                    Ok(name) => name,
+                    Err(_) => {
+                        walked.errors.push(LibFileError::InvalidUtf8(entrystr));
+                        continue;
+                    }
+                };
+                let relpath = entrypath.strip_prefix(rootpath).unwrap_or(&entrypath).to_string_lossy().into_owned();
+                let matches_any = |patterns: &[String]| patterns.iter()
+                    .any(|pattern| file_glob_match(pattern, &name) || file_glob_match(pattern, &relpath));
+
+                if (!self.hidden && name.starts_with('.')) || matches_any(&self.excludes) {
+                    continue;
+                }
+
+                // region Find out what the entry is, following a link if allowed.
+                let filetype = match entry.file_type() {
+                    Ok(filetype) => filetype,
+                    Err(err) => {
+                        walked.errors.push(LibFileError::from_io(err, &entrystr));
+                        continue;
+                    }
+                };
+                let (isdir, isfile) = match (filetype.is_symlink(), self.followlinks) {
+                    (false, _) => (filetype.is_dir(), filetype.is_file()),
+                    (true, false) => continue,
+                    (true, true) => match fs::metadata(&entrypath) {
+                        Ok(metadata) => (metadata.is_dir(), metadata.is_file()),
+                        Err(err) => {
+                            walked.errors.push(LibFileError::from_io(err, &entrystr));
+                            continue;
+                        }
+                    },
+                };
+                // endregion
+
+                if isdir {
+                    if self.maxdepth.is_some_and(|maxdepth| depth + 1 > maxdepth) {
+                        continue;  // Not entered, so not marked visited either.
+                    }
+                    let canonical = match fs::canonicalize(&entrypath) {
+                        Ok(canonical) => canonical,
+                        Err(err) => {
+                            walked.errors.push(LibFileError::from_io(err, &entrystr));
+                            continue;
+                        }
+                    };
+                    if visited.insert(canonical) {  // `false` means a link led back to a directory already walked.
+                        self.walk_dir(rootpath, &entrypath, depth + 1, visited, walked);
+                    }
+                } else if isfile
+                    && (self.includes.is_empty() || matches_any(&self.includes))
+                    && (self.extensions.is_empty() || self.extensions.iter()
+                        .any(|extnsn| extnsn.eq_ignore_ascii_case(file_check_extension(&name).1))) {
+                    walked.files.push(entrystr);
+                }
+            }
+        }
+    }
+
+    /// What kind of thing a directory entry is.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum EntryKind {
+        File,
+        Dir,
+        /// A symbolic link.  The entry's size and time are those of the file
+        /// it points to, or of the link itself if it is broken.
+        Symlink,
+        /// Anything else, such as a device or a named pipe.
+        Other,
+    }
+
+    /// One entry of a directory, as listed by `file_list_dir()`.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct DirEntryInfo {
+        pub name: String,
+        pub path: String,
+        pub kind: EntryKind,
+        /// The size in bytes.
+        pub size: u64,
+        /// When the entry was last modified, if the system says.
+        pub modified: Option<SystemTime>,
+        /// The extension, without the dot, as found by `file_check_extension()`.
+        /// Empty if there is none.
+        pub extension: String,
+    }
 
-        file_names
+    /// What `file_sort_entries()` sorts by.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SortBy {
+        /// Name, in natural order:  case is ignored and runs of digits are
+        /// compared as numbers, so `unit2` comes before `unit10`.
+        Name,
+        /// Extension (case ignored), then name.
+        Extension,
+        /// Size, then name.
+        Size,
+        /// Modification time, then name.  Entries with no time come first
+        /// when ascending.
+        Modified,
+    }
+
+    /// Which way `file_sort_entries()` sorts.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SortOrder {
+        Ascending,
+        Descending,
+    }
+
+    /// Lists every entry of a directory (files, subdirectories, links and
+    /// the rest) with its metadata, sorted by name.  Use
+    /// `file_sort_entries()` to sort them another way, and filter on `kind`
+    /// to keep only files or directories.  `dirpath` is expanded with
+    /// `dir_expand()`.  Names that aren't valid UTF-8 get `U+FFFD` in
+    /// place of the bad bytes, and entries whose metadata can't be read are
+    /// skipped, so one odd file doesn't hide the rest.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     // Newest bank first, for a "pick a bank" menu.
+    ///     let mut entries = match file_list_dir("~/qbnk_data/banks") {
+    ///         Ok(entries) => entries,
+    ///         Err(err) => {
+    ///             println!("\n {} \n", err);
+    ///             return;
+    ///         }
+    ///     };
+    ///     entries.retain(|entry| entry.kind == EntryKind::File && entry.extension == "bnk");
+    ///     file_sort_entries(&mut entries, SortBy::Modified, SortOrder::Descending);
+    ///
+    ///     let names: Vec<String> = entries.iter().map(|entry| entry.name.clone()).collect();
+    ///     println!("\n You chose {} \n", file_namemenu(&names));
+    /// }
+    /// ```
+    ///
+    pub fn file_list_dir(dirpath: &str) -> Result<Vec<DirEntryInfo>, LibFileError> {
+        let dir_entries = fs::read_dir(dir_expand(dirpath)?)
+            .map_err(|err| LibFileError::from_io(err, dirpath))?;
+
+        let mut entries = Vec::new();
+        for entry in dir_entries.flatten() {
+            let entrypath = entry.path().to_string_lossy().into_owned();
+            let name = entry.file_name().to_string_lossy().into_owned();
+
+            // region Read the metadata, looking through links where they lead somewhere.
+            let ownmetadata = match entry.metadata() {
+                Ok(ownmetadata) => ownmetadata,
+                Err(_) => continue,
+            };
+            let filetype = ownmetadata.file_type();
+            let kind = match (filetype.is_symlink(), filetype.is_dir(), filetype.is_file()) {
+                (true, _, _) => EntryKind::Symlink,
+                (false, true, _) => EntryKind::Dir,
+                (false, false, true) => EntryKind::File,
+                _ => EntryKind::Other,
+            };
+            let metadata = match kind {
+                EntryKind::Symlink => fs::metadata(entry.path()).unwrap_or(ownmetadata),  // Broken links keep their own.
+                _ => ownmetadata,
+            };
+            // endregion
+
+            entries.push(DirEntryInfo {
+                extension: file_check_extension(&name).1.to_string(),
+                name,
+                path: entrypath,
+                kind,
+                size: metadata.len(),
+                modified: metadata.modified().ok(),
+            });
+        }
+
+        file_sort_entries(&mut entries, SortBy::Name, SortOrder::Ascending);
+        Ok(entries)
+    }
+
+    /// Sorts directory entries in place.  Entries that tie on `sortby` are
+    /// put in name order, so the result is the same every time.
+    pub fn file_sort_entries(entries: &mut [DirEntryInfo], sortby: SortBy, order: SortOrder) {
+        entries.sort_by(|first, second| {
+            let byname = file_natural_cmp(&first.name, &second.name);
+            let ordering = match sortby {
+                SortBy::Name => byname,
+                SortBy::Extension => first.extension.to_lowercase().cmp(&second.extension.to_lowercase())
+                    .then(byname),
+                SortBy::Size => first.size.cmp(&second.size).then(byname),
+                SortBy::Modified => first.modified.cmp(&second.modified).then(byname),
+            };
+            match order {
+                SortOrder::Ascending => ordering,
+                SortOrder::Descending => ordering.reverse(),
+            }
+        });
+    }
+
+    /// Compares two names in natural order:  runs of digits by their value,
+    /// everything else without regard to case.  Names that still tie are
+    /// compared exactly.
+    fn file_natural_cmp(first: &str, second: &str) -> Ordering {
+        let mut firstchars = first.chars().peekable();
+        let mut secondchars = second.chars().peekable();
+
+        loop {
+            let (firstch, secondch) = match (firstchars.peek(), secondchars.peek()) {
+                (None, None) => return first.cmp(second),
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(firstch), Some(secondch)) => (*firstch, *secondch),
+            };
+
+            let ordering = match (firstch.is_ascii_digit(), secondch.is_ascii_digit()) {
+                (true, true) => {
+                    // Compare the digit runs as numbers:  drop leading zeros, then
+                    // the longer run is bigger, then compare digit by digit.
+                    let firstrun = file_take_digits(&mut firstchars);
+                    let secondrun = file_take_digits(&mut secondchars);
+                    let firsttrim = firstrun.trim_start_matches('0');
+                    let secondtrim = secondrun.trim_start_matches('0');
+                    firsttrim.len().cmp(&secondtrim.len()).then_with(|| firsttrim.cmp(secondtrim))
+                }
+                _ => {
+                    firstchars.next();
+                    secondchars.next();
+                    firstch.to_lowercase().cmp(secondch.to_lowercase())
+                }
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+    }
+
+    /// Takes the run of ASCII digits at the front of `chars`.
+    fn file_take_digits(chars: &mut Peekable<Chars>) -> String {
+        let mut digits = String::new();
+        while chars.peek().is_some_and(|ch| ch.is_ascii_digit()) {
+            digits.extend(chars.next());
+        }
+        digits
     }
 
     /// Create a menu from a vector of file names.  Returns the item chosen
@@ -1174,88 +4959,117 @@ pub mod file_mngmnt {
     }
 
     /// Read the elements from a given file, storing them in a passed vector.
+    /// Each line of the file holds one element.  If a line cannot be parsed,
+    /// `LibFileError::Parse` reports the line number (counting from 1).
+    /// Elements read before the bad line are left in the vector.
+    ///
+    /// Example:
     ///
-    pub fn file_read_to_vec<T: FromStr>(fname: &str, vctr: &mut Vec<T>)
+    /// ```ignore
+    /// fn main() {
+    ///     let mut numbers: Vec<i64> = Vec::new();
+    ///     match file_read_to_vec("/home/somebody/numbers.lst", &mut numbers) {
+    ///         Ok(()) => println!("\n Read {:?} \n", numbers),
+    ///         Err(err) => println!("\n {} \n", err),
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn file_read_to_vec<T: FromStr>(fname: &str, vctr: &mut Vec<T>) -> Result<(), LibFileError>
         where <T as FromStr>::Err: Debug {
-        let file = File::open(fname).unwrap();
+        let file = File::open(fname)
+            .map_err(|err| LibFileError::from_io(err, fname))?;
         let reader = BufReader::new(file);
 
-        for line in reader.lines() {
-            let line = line.unwrap();
-            let num = line.parse().unwrap();
-            vctr.push(num);
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(|err| LibFileError::from_io(err, fname))?;
+            match line.parse() {
+                Ok(num) => vctr.push(num),
+                Err(err) => {
+                    return Err(LibFileError::Parse {
+                        path: fname.to_string(),
+                        line: index + 1,
+                        message: format!("{:?}", err),
+                    });
+                }
+            }
         }
+        Ok(())
     }
 
     /// Saves a vector to a file.
     ///
     pub fn file_save_vec<T: std::fmt::Display>(fname: &str, vector: &[T]) ->
-                                                         std::io::Result<()> {
-        let mut file = File::create(fname)?;
+                                                         Result<(), LibFileError> {
+        let mut file = File::create(fname)
+            .map_err(|err| LibFileError::from_io(err, fname))?;
         for num in vector {
             let num_str = num.to_string();
-            file.write_all(num_str.as_bytes())?;
-            file.write_all(b"\n")?;
+            file.write_all(num_str.as_bytes())
+                .map_err(|err| LibFileError::from_io(err, fname))?;
+            file.write_all(b"\n")
+                .map_err(|err| LibFileError::from_io(err, fname))?;
         }
         Ok(())
     }
 
     /// Lets the user input a path string, checks that path for validity, then
     /// lets the user choose a file to work with.  Returns both
-    /// the path and the chosen file name.
+    /// the path and the chosen file name.  The file name is empty if the
+    /// directory holds no files with the passed extension.  Returns
+    /// `LibFileError::Cancelled` if the user gave up on entering a usable path.
     /// Example:
     ///
     ///     fn main() {
     ///         let extension = "lst";  // Be sure to check using a non-existing extension.
-    ///         let existing_fname = file_choose_from_existing(&extension);
-    ///         if existing_fname == "".to_string() {
-    ///             return
-    ///         } else {
-    ///             println!("\n You chose the name   {}   \n", existing_fname);
+    ///         match file_choose_from_existing(&extension) {
+    ///             Ok((_, existing_fname)) if existing_fname.is_empty() => return,
+    ///             Ok((dirpath, existing_fname)) => {
+    ///                 println!("\n You chose the name   {}   in   {} \n", existing_fname, dirpath);
+    ///             }
+    ///             Err(err) => println!("\n {} \n", err),
     ///         }
     ///     }
-    pub fn file_choose_from_existing(extsn: &str) -> (String, String) {
+    pub fn file_choose_from_existing(extsn: &str) -> Result<(String, String), LibFileError> {
         let dirpath = input_string_prompt("Please enter the path for the directory where this file has been saved:  ");
-        let dirok = dir_checkexist_fix(&dirpath);
-        if dirok.0 == false {
-            println!("\n The path \n   {} \n was not usable and was not corrected. \n", dirpath);
-            panic!("Invalid and uncorrected path entered.");
-            // Maybe eventually return a result that the main program can use to
-            // redirect user's activity.
-        }
+        let dirpath = dir_checkexist_fix(&dirpath)?;
 
-        let is_empty = dir_check_empty(&dirpath).unwrap();
+        let is_empty = dir_check_empty(&dirpath)?;
         if is_empty {
             println!("\n That directory is empty.");
-            return (dirpath, "".to_string());
+            return Ok((dirpath, "".to_string()));
         }
-        let mut file_names = file_get_dir_list(dirpath.as_str());
+        let mut file_names = file_get_dir_list(dirpath.as_str())?;
 
         file_del_unwanted_names(&mut file_names, extsn);
-        if file_names.len() == 0 {
+        if file_names.is_empty() {
             println!("\n There are no *.{} files in this directory.", extsn);
-            return (dirpath, "".to_string());
+            return Ok((dirpath, "".to_string()));
         }
 
         let chosen = file_namemenu(&file_names);
-        (dirpath, chosen)
+        Ok((dirpath, chosen))
     }
 
     /// Choose a name for your file from existing files in a given directory.
     ///
     /// Choose a file name to use for saving.
     /// The function adds an extension to the file name and then
-    /// appends it to the path.
+    /// appends it to the path, which is expanded with `dir_expand()`.
     ///
     /// Example:
     ///
     ///         fn main() {
-    ///             let dirpath = file_choose_new_fname("lst");
-    ///             println!("\n In main() the new path & fname is:  {}", dirpath);
+    ///             let usedir = "/home/somebody/lists".to_string();
+    ///             match file_choose_new_fname("lst", &usedir) {
+    ///                 Ok(dirpath) => println!("\n In main() the new path & fname is:  {}", dirpath),
+    ///                 Err(err) => println!("\n {}", err),
+    ///             }
     ///
     ///             println!("\n All is okay!!  :>) \n");
     ///         }
-    pub fn file_choose_new_fname(extnsn: &str, dirpath: &String) -> String {
+    pub fn file_choose_new_fname(extnsn: &str, dirpath: &String) -> Result<String, LibFileError> {
+        let dirpath = dir_expand(dirpath)?;
         let mut fname: String;
         let mut usepath: String;
         loop {
@@ -1267,7 +5081,7 @@ pub mod file_mngmnt {
             usepath = usepath + "/" + fname.as_str();  // Try using format!() here.
             let fullpath = Path::new(&usepath);
             let exists = fullpath.try_exists()
-                .expect("Error when checking the existence of the file");
+                .map_err(|err| LibFileError::from_io(err, &usepath))?;
             if exists {
                 println!("\n That file   {}   already exists.", &fname);
                 let choice = input_bool_prompt("\n Do you want to overwrite the file? ");
@@ -1276,7 +5090,54 @@ pub mod file_mngmnt {
             }
             break;
         }
-        usepath
+        Ok(usepath)
+    }
+
+    /// Finds a free name for a file that would otherwise overwrite `fullpath`.
+    ///
+    /// A number in parentheses is added to the end of the file's stem, starting
+    /// at 1 and counting up until no such file exists.  If nothing exists at
+    /// `fullpath` in the first place, it is returned unchanged.
+    ///
+    /// Example:
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     // With `notes.lst` and `notes (1).lst` already in the directory:
+    ///     match file_next_free_path("/home/somebody/lists/notes.lst") {
+    ///         Ok(usepath) => println!("{}", usepath),  // .../notes (2).lst
+    ///         Err(err) => println!("\n {}", err),
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn file_next_free_path(fullpath: &str) -> Result<String, LibFileError> {
+        let path_exists = |usepath: &str| Path::new(usepath).try_exists()
+            .map_err(|err| LibFileError::from_io(err, usepath));
+
+        if !path_exists(fullpath)? {
+            return Ok(fullpath.to_string());
+        }
+
+        let usepath = Path::new(fullpath);
+        let stem = match usepath.file_stem() {
+            Some(stem) => stem.to_string_lossy().into_owned(),
+            None => return Err(LibFileError::InvalidPath(fullpath.to_string())),
+        };
+        let extnsn = match usepath.extension() {
+            Some(extnsn) => format!(".{}", extnsn.to_string_lossy()),
+            None => String::new(),
+        };
+
+        let mut count = 1;
+        loop {
+            let candidate = usepath.with_file_name(format!("{} ({}){}", stem, count, extnsn));
+            let candidate = candidate.to_string_lossy().into_owned();
+            if !path_exists(&candidate)? {
+                return Ok(candidate);
+            }
+            count += 1;
+        }
     }
 
     /// Input a file name and append an extension to it.
@@ -1302,16 +5163,17 @@ pub mod file_mngmnt {
 
     /// This function is not yet finished.  Don't use it.
     ///
-    pub fn file_chkfname( fname: &String, dirpath: &String) -> String {
+    pub fn file_chkfname( fname: &String, dirpath: &String) -> Result<String, LibFileError> {
 // This is not yet ready.  What are you returning?
 
+        let dirpath = dir_expand(dirpath)?;
         let mut usepath: String;
         loop {
             usepath = dirpath.clone();
             usepath = usepath + "/" + fname.as_str();
             let fullpath = Path::new(&usepath);
             let exists = fullpath.try_exists()
-                .expect("Error when checking the existence of the file");
+                .map_err(|err| LibFileError::from_io(err, &usepath))?;
             if exists {
                 println!("\n That file   {}   already exists.", &fname);
                 let choice = input_bool_prompt("\n Do you want to overwrite the file? ");
@@ -1322,12 +5184,325 @@ pub mod file_mngmnt {
             }
             break;
         }
-        usepath
+        Ok(usepath)
     }
 
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::{env, process};
+
+        /// A fresh scratch directory for one test, holding `files` (paths
+        /// relative to it, with any directories they need).
+        fn scratch_tree(name: &str, files: &[&str]) -> PathBuf {
+            let dir = env::temp_dir().join(format!("lib_file_mngmnt_{}_{}", name, process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            for file in files {
+                let path = dir.join(file);
+                fs::create_dir_all(path.parent().expect("A scratch file has a parent."))
+                    .expect("The scratch directories can be made.");
+                fs::write(&path, "").expect("The scratch file can be written.");
+            }
+            fs::create_dir_all(&dir).expect("The scratch directory can be made.");
+            dir
+        }
+
+        /// The walked files as paths relative to `dir`.
+        fn relative(walked: &WalkResult, dir: &Path) -> Vec<String> {
+            walked.files.iter()
+                .map(|file| Path::new(file).strip_prefix(dir).expect("Walked files are under the root.")
+                    .to_string_lossy().into_owned())
+                .collect()
+        }
+
+        #[test]
+        fn recent_files_survive_a_save_and_load() {
+            let dir = scratch_tree("recentsave", &["a.lst", "b.lst", "c.lst"]);
+            let storepath = dir.join("state/recent").to_string_lossy().into_owned();
+            let file = |name: &str| dir.join(name).to_string_lossy().into_owned();
 
+            let mut recent = RecentFiles::load(&storepath, 5).expect("A missing list loads empty.");
+            assert!(recent.paths().is_empty());
+            for name in ["a.lst", "b.lst", "c.lst"] {
+                recent.add(&file(name)).expect("The path can be stored.");
+            }
+            recent.save().expect("The list and its directory can be written.");
+
+            let reloaded = RecentFiles::load(&storepath, 5).expect("The saved list can be read.");
+            assert_eq!(reloaded.paths(), [file("c.lst"), file("b.lst"), file("a.lst")]);
+            let _ = fs::remove_dir_all(&dir);
+        }
+
+        #[test]
+        fn recent_files_move_repeats_to_the_front_and_keep_max_len() {
+            let mut recent = RecentFiles::load("/no/such/dir/recent", 3).expect("A missing list loads empty.");
+            for path in ["/a", "/b", "/c", "/a"] {
+                recent.add(path).expect("The path can be stored.");
+            }
+            assert_eq!(recent.paths(), ["/a", "/c", "/b"]);
+
+            recent.add("/d").expect("The path can be stored.");
+            assert_eq!(recent.paths(), ["/d", "/a", "/c"]);  // The oldest fell off.
+            recent.set_max_len(1);
+            assert_eq!(recent.paths(), ["/d"]);
+            assert_eq!(recent.max_len(), 1);
+        }
+
+        #[test]
+        fn recent_files_prune_missing_files() {
+            let dir = scratch_tree("recentprune", &["kept.lst", "gone.lst"]);
+            let storepath = dir.join("recent").to_string_lossy().into_owned();
+            let kept = dir.join("kept.lst").to_string_lossy().into_owned();
+            let gone = dir.join("gone.lst").to_string_lossy().into_owned();
+
+            let mut recent = RecentFiles::load(&storepath, 5).expect("A missing list loads empty.");
+            recent.add(&kept).expect("The path can be stored.");
+            recent.add(&gone).expect("The path can be stored.");
+            recent.save().expect("The list can be written.");
+            fs::remove_file(&gone).expect("The scratch file can be removed.");
+
+            recent.prune();
+            assert_eq!(recent.paths(), [kept.as_str()]);
+            let reloaded = RecentFiles::load(&storepath, 5).expect("The saved list can be read.");
+            assert_eq!(reloaded.paths(), [kept.as_str()]);  // Loading prunes too.
+            let _ = fs::remove_dir_all(&dir);
+        }
+
+        #[test]
+        fn recent_files_reject_line_breaks() {
+            let mut recent = RecentFiles::load("/no/such/dir/recent", 5).expect("A missing list loads empty.");
+            recent.add("/a").expect("The path can be stored.");
+            for path in ["/b\n/c", "/b\r", ""] {
+                assert!(matches!(recent.add(path), Err(LibFileError::InvalidPath(_))));
+            }
+            assert_eq!(recent.paths(), ["/a"]);
+        }
+
+        #[test]
+        fn natural_order_compares_digit_runs_as_numbers() {
+            assert_eq!(file_natural_cmp("unit2", "unit10"), Ordering::Less);
+            assert_eq!(file_natural_cmp("unit10", "unit2"), Ordering::Greater);
+            assert_eq!(file_natural_cmp("unit007", "unit7"), Ordering::Less);  // Same value, so the exact names decide.
+            assert_eq!(file_natural_cmp("unit", "unit1"), Ordering::Less);
+            assert_eq!(file_natural_cmp("Banks", "algebra"), Ordering::Greater);
+        }
+
+        #[test]
+        fn natural_order_breaks_case_ties_the_same_way_every_time() {
+            assert_eq!(file_natural_cmp("Notes", "notes"), Ordering::Less);
+            assert_eq!(file_natural_cmp("notes", "Notes"), Ordering::Greater);
+            assert_eq!(file_natural_cmp("notes", "notes"), Ordering::Equal);
+        }
+
+        #[test]
+        fn entries_sort_by_size_and_time_both_ways() {
+            let entry = |name: &str, size: u64, secs: u64| DirEntryInfo {
+                name: name.to_string(),
+                path: format!("/lists/{}", name),
+                kind: EntryKind::File,
+                size,
+                modified: Some(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs)),
+                extension: file_check_extension(name).1.to_string(),
+            };
+            let names = |entries: &[DirEntryInfo]| entries.iter().map(|entry| entry.name.clone()).collect::<Vec<String>>();
+            let mut entries = vec![entry("b.lst", 20, 300), entry("a.lst", 20, 100), entry("c.lst", 5, 200)];
+
+            file_sort_entries(&mut entries, SortBy::Size, SortOrder::Ascending);
+            assert_eq!(names(&entries), vec!["c.lst", "a.lst", "b.lst"]);
+            file_sort_entries(&mut entries, SortBy::Size, SortOrder::Descending);
+            assert_eq!(names(&entries), vec!["b.lst", "a.lst", "c.lst"]);
+            file_sort_entries(&mut entries, SortBy::Modified, SortOrder::Ascending);
+            assert_eq!(names(&entries), vec!["a.lst", "c.lst", "b.lst"]);
+            file_sort_entries(&mut entries, SortBy::Modified, SortOrder::Descending);
+            assert_eq!(names(&entries), vec!["b.lst", "c.lst", "a.lst"]);
+        }
+
+        #[test]
+        fn listing_sorts_entries_naturally_and_reads_their_kind() {
+            let dir = scratch_tree("listdir", &["unit10.lst", "unit2.lst", "sub/inner.lst"]);
+            let entries = file_list_dir(&dir.to_string_lossy()).expect("The scratch directory can be listed.");
+
+            let listed: Vec<(&str, EntryKind)> = entries.iter().map(|entry| (entry.name.as_str(), entry.kind)).collect();
+            assert_eq!(listed, vec![("sub", EntryKind::Dir), ("unit2.lst", EntryKind::File),
+                                    ("unit10.lst", EntryKind::File)]);
+            assert_eq!(entries[1].extension, "lst");
+            let _ = fs::remove_dir_all(&dir);
+        }
+
+        #[test]
+        fn walk_skips_hidden_entries_unless_asked() {
+            let dir = scratch_tree("walkhidden", &["shown.lst", ".hidden.lst", ".cache/inside.lst"]);
+            let rootdir = dir.to_string_lossy();
+
+            let walked = DirWalker::new(&rootdir).walk().expect("The scratch tree can be walked.");
+            assert_eq!(relative(&walked, &dir), vec!["shown.lst"]);
+
+            let walked = DirWalker::new(&rootdir).hidden(true).walk().expect("The scratch tree can be walked.");
+            assert_eq!(relative(&walked, &dir), vec![".cache/inside.lst", ".hidden.lst", "shown.lst"]);
+            let _ = fs::remove_dir_all(&dir);
+        }
+
+        #[test]
+        fn walk_includes_and_excludes_by_name_and_relative_path() {
+            let dir = scratch_tree("walkglobs", &["keep.lst", "skip.bak", "drafts/draft.lst", "old_a/a.lst",
+                                                  "units/unit1.lst", "units/notes.txt"]);
+            let rootdir = dir.to_string_lossy();
+
+            let walked = DirWalker::new(&rootdir)
+                .include("*.lst")
+                .exclude("drafts/*")
+                .exclude("old_*")
+                .walk()
+                .expect("The scratch tree can be walked.");
+            assert_eq!(relative(&walked, &dir), vec!["keep.lst", "units/unit1.lst"]);
+            assert!(walked.errors.is_empty());
+
+            let walked = DirWalker::new(&rootdir).max_depth(1).extensions(&["BAK"]).walk()
+                .expect("The scratch tree can be walked.");
+            assert_eq!(relative(&walked, &dir), vec!["skip.bak"]);
+            let _ = fs::remove_dir_all(&dir);
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn walk_survives_link_loops_and_reports_broken_links() {
+            use std::os::unix::fs::symlink;
+
+            let dir = scratch_tree("walkloop", &["a/file.lst"]);
+            symlink(&dir, dir.join("a/loop")).expect("The loop link can be made.");
+            symlink(dir.join("nothere"), dir.join("broken")).expect("The broken link can be made.");
+            let rootdir = dir.to_string_lossy();
+
+            let walked = DirWalker::new(&rootdir).walk().expect("The scratch tree can be walked.");
+            assert_eq!(relative(&walked, &dir), vec!["a/file.lst"]);
+            assert!(walked.errors.is_empty());  // Links are skipped, not followed.
+
+            let walked = DirWalker::new(&rootdir).follow_symlinks(true).walk()
+                .expect("The scratch tree can be walked.");
+            assert_eq!(relative(&walked, &dir), vec!["a/file.lst"]);
+            assert_eq!(walked.errors.len(), 1);
+            assert!(matches!(&walked.errors[0], LibFileError::NotFound(path) if path.ends_with("broken")));
+            let _ = fs::remove_dir_all(&dir);
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn walk_past_max_depth_does_not_hide_a_shallower_copy() {
+            use std::os::unix::fs::symlink;
+
+            let dir = scratch_tree("walkdepth", &["z/keep.lst"]);
+            fs::create_dir_all(dir.join("a")).expect("The scratch directory can be made.");
+            symlink(dir.join("z"), dir.join("a/toz")).expect("The link can be made.");
+
+            // `a/toz` comes first but is too deep to enter; `z` must still be walked.
+            let walked = DirWalker::new(&dir.to_string_lossy()).follow_symlinks(true).max_depth(2).walk()
+                .expect("The scratch tree can be walked.");
+            assert_eq!(relative(&walked, &dir), vec!["z/keep.lst"]);
+            let _ = fs::remove_dir_all(&dir);
+        }
+    }
 
 
 
 } // End of file_mngmnt module
 
+
+/// # The error type shared by the functions in this crate.
+///
+pub mod file_errors {
+    use std::{error::Error, fmt, io};
+    use fltk::prelude::FltkError;
+
+    /// Describes what went wrong in one of this crate's functions.
+    ///
+    /// Every variant that involves a file or directory carries the path
+    /// that caused the trouble, so the message can be shown to the user
+    /// as-is.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     match file_read_to_string("/home/somebody/nothere.lst") {
+    ///         Ok(contents) => println!("{}", contents),
+    ///         Err(LibFileError::NotFound(path)) => println!("Please check the name:  {}", path),
+    ///         Err(LibFileError::Cancelled) => {},
+    ///         Err(err) => println!("Something else went wrong:  {}", err),
+    ///     }
+    /// }
+    /// ```
+    ///
+    #[derive(Debug)]
+    pub enum LibFileError {
+        /// The file or directory does not exist.
+        NotFound(String),
+        /// The operating system would not let us read or write the path.
+        PermissionDenied(String),
+        /// A path, a file name, or the contents of a file is not valid UTF-8.
+        InvalidUtf8(String),
+        /// The path cannot be used the way it was asked for (e.g. it has no file name on the end).
+        InvalidPath(String),
+        /// A line of a file could not be parsed.  Line numbers count from 1.
+        Parse { path: String, line: usize, message: String },
+        /// The user cancelled the prompt or dialog.
+        Cancelled,
+        /// A set of dialog filters was written incorrectly.
+        InvalidFilter(String),
+        /// A path names an environment variable (`$NAME` or `${NAME}`) that isn't set.
+        UnsetVariable { path: String, name: String },
+        /// A file dialog could not be set up or shown.
+        Dialog(String),
+        /// Any other i/o error, along with the path involved.
+        Io { path: String, source: io::Error },
+    }
+
+    impl LibFileError {
+        /// Turns an `io::Error` into the matching `LibFileError`, remembering
+        /// the path that was being worked on.
+        pub fn from_io(err: io::Error, path: &str) -> LibFileError {
+            match err.kind() {
+                io::ErrorKind::NotFound => LibFileError::NotFound(path.to_string()),
+                io::ErrorKind::PermissionDenied => LibFileError::PermissionDenied(path.to_string()),
+                io::ErrorKind::InvalidData => LibFileError::InvalidUtf8(path.to_string()),
+                _ => LibFileError::Io { path: path.to_string(), source: err },
+            }
+        }
+    }
+
+    impl fmt::Display for LibFileError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                LibFileError::NotFound(path) => write!(f, "The path {} does not exist.", path),
+                LibFileError::PermissionDenied(path) => write!(f, "Permission denied for {}.", path),
+                LibFileError::InvalidUtf8(path) => write!(f, "{} is not valid UTF-8.", path),
+                LibFileError::InvalidPath(path) => write!(f, "The path {} cannot be used here.", path),
+                LibFileError::Parse { path, line, message } => {
+                    write!(f, "Could not parse line {} of {}:  {}", line, path, message)
+                }
+                LibFileError::Cancelled => write!(f, "Cancelled by the user."),
+                LibFileError::InvalidFilter(message) => write!(f, "Invalid file filter:  {}", message),
+                LibFileError::UnsetVariable { path, name } => {
+                    write!(f, "The path {} uses ${}, which is not set.", path, name)
+                }
+                LibFileError::Dialog(message) => write!(f, "The file dialog failed:  {}", message),
+                LibFileError::Io { path, source } => write!(f, "Error with {}:  {}", path, source),
+            }
+        }
+    }
+
+    impl Error for LibFileError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                LibFileError::Io { source, .. } => Some(source),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<FltkError> for LibFileError {
+        fn from(err: FltkError) -> LibFileError {
+            LibFileError::Dialog(err.to_string())
+        }
+    }
+
+}  // End of file_errors module.
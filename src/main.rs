@@ -3,6 +3,7 @@
     VERSION = "0.2.0";
 */
 use fltk::app;
+use lib_file::dir_mngmnt::LastDirs;
 use lib_file::file_fltk::*;
 
 
@@ -10,16 +11,33 @@ fn main() {
    let _app = app::App::default(); // must live while dialogs are used
 
 
+    // The dialog opens where the last file was saved; `usedir` is only the first-run default.
+    match LastDirs::load(&LastDirs::default_path("lib_file_demo")) {
+        Ok(lastdirs) => fltk_set_lastdirs(Some(lastdirs)),
+        Err(err) => println!("\n Starting without remembered directories:  {} \n", err),
+    }
+
     //let usedir = "/home/jtreagan/programming/mine/qbnk_rb7/src/qbnk_data/banks";
     let usedir = "/home/jtreagan/programming/mine/empty";
 
-    let extsnvec = vec!["Lists", "*.lst", "Variables", "*.vrbl", "Banks", "*.bnk", "Text", "*.txt", "All Files", "*.*"];
+    let filters = match FilterSet::new(&[("Lists", "*.lst"), ("Variables", "*.vrbl"),
+                                          ("Banks", "*.bnk"), ("Text", "*.txt")]) {
+        Ok(filters) => filters.with_all_files(),
+        Err(err) => {
+            println!("\n {} \n", err);
+            return;
+        }
+    };
 
-    let path = file_browse_tosave(usedir, "NAME_OF_FILE_TO_SAVE", &extsnvec);
+    let path = file_browse_tosave(usedir, "NAME_OF_FILE_TO_SAVE", &filters);
 
    // app.run().unwrap();
 
-    println!("\n {} \n", path);
+    match path {
+        Ok(Some(path)) => println!("\n {} \n", path),
+        Ok(None) => println!("\n The dialog was cancelled. \n"),
+        Err(err) => println!("\n The dialog failed:  {} \n", err),
+    }
 }
 
 
@@ -1,42 +1,506 @@
-use std::path::Path;
+//! Shows two text files next to each other, with the lines that differ
+//! highlighted.  Written for comparing two revisions of a question bank,
+//! but any pair of text files will do.
 
-pub fn file_browse_tosave(sggstdpath: &str, sggstdname: &str, sggstdextnsn: &str, wintitle: &str) -> String {
+use fltk::{app, enums::{Color, Event, Font}, frame::Frame, prelude::*, window::Window};
+use fltk::text::{StyleTableEntryExt, TextAttr, TextBuffer, TextDisplay};
+use fltk::valuator::Scrollbar;
+use crate::file_errors::LibFileError;
+use crate::file_fltk::{DialogBuilder, DialogMode};
+use crate::file_mngmnt::file_read_to_string;
 
-    // region Check that the passed directory exists and `startpath` is ready.
-    let track = dir_check_valid(&mut sggstdpath.to_string());  // Defaults to home directory on err.
-    let startpath = Path::new(track.as_str());
+/// How one row of a side-by-side comparison differs between the files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// The line is the same in both files.
+    Same,
+    /// The line is only in the right-hand (newer) file.
+    Added,
+    /// The line is only in the left-hand (older) file.
+    Removed,
+    /// The line was rewritten:  the left and right lines stand in for each other.
+    Changed,
+}
+
+/// One row of a side-by-side comparison.  A side is `None` where that file
+/// has no line to show in this row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffRow {
+    pub kind: DiffKind,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+/// Lines up two lists of lines for showing side by side.
+///
+/// Lines the files share are found with Myers' O(ND) difference algorithm,
+/// in its linear-space form, after the lines both files start and end with
+/// are set aside.  Two long revisions that differ in a few places cost
+/// little time and no more memory than the lines themselves.  Between two
+/// shared lines, removed and added lines are paired off as `Changed` rows;
+/// whatever is left over is `Removed` or `Added`.
+///
+/// # Example
+///
+/// ```ignore
+/// fn main() {
+///     let older: Vec<String> = vec!["a".into(), "b".into(), "c".into()];
+///     let newer: Vec<String> = vec!["a".into(), "B".into(), "c".into(), "d".into()];
+///
+///     for row in file_diff_lines(&older, &newer) {
+///         println!("{:?}  {:?}  {:?}", row.kind, row.left, row.right);
+///     }
+///     // Same, Changed, Same, Added
+/// }
+/// ```
+///
+pub fn file_diff_lines(left: &[String], right: &[String]) -> Vec<DiffRow> {
+
+    // region Mark the lines the two files share.
+    let mut leftkept = vec![false; left.len()];
+    let mut rightkept = vec![false; right.len()];
+    file_diff_mark(left, right, &mut leftkept, &mut rightkept);
     // endregion
 
-    // region Call a dialog browser and set it to the passed directory.
-    let mut fchooser = dialog::NativeFileChooser
-    ::new(dialog::NativeFileChooserType
-    ::BrowseSaveFile);
-    fchooser.set_directory(&startpath).expect("Cannot set directory.");
+    // region Walk both files, collecting rows and pairing removed lines with added ones.
+    let mut rows = Vec::new();
+    let mut removed: Vec<String> = Vec::new();
+    let mut added: Vec<String> = Vec::new();
+    let (mut lidx, mut ridx) = (0, 0);
+
+    while lidx < left.len() || ridx < right.len() {
+        if lidx < left.len() && !leftkept[lidx] {
+            removed.push(left[lidx].clone());
+            lidx += 1;
+        } else if ridx < right.len() && !rightkept[ridx] {
+            added.push(right[ridx].clone());
+            ridx += 1;
+        } else {
+            // Both lines are kept, and kept lines pair up in order.
+            file_diff_flush(&mut rows, &mut removed, &mut added);
+            rows.push(DiffRow { kind: DiffKind::Same, left: Some(left[lidx].clone()), right: Some(right[ridx].clone()) });
+            lidx += 1;
+            ridx += 1;
+        }
+    }
+    file_diff_flush(&mut rows, &mut removed, &mut added);
     // endregion
 
-    // region  Add the passed extension to the suggested file name.
-    let ext_to_append = sggstdextnsn.strip_prefix("*.").unwrap_or(sggstdextnsn);
-    let usename = format!("{}.{}", sggstdname, ext_to_append);
-    fchooser.set_preset_file(usename.as_str());
+    rows
+}
+
+/// Marks, in `leftkept` and `rightkept`, the lines of a shortest edit from
+/// `left` to `right` that stay as they are.  The shared start and end are
+/// marked first; what is left in between is split at its middle snake and
+/// each half is marked the same way.
+fn file_diff_mark(left: &[String], right: &[String], leftkept: &mut [bool], rightkept: &mut [bool]) {
+
+    // region Set aside the lines both sides start and end with.
+    let prefix = left.iter().zip(right).take_while(|(lline, rline)| lline == rline).count();
+    let suffix = left[prefix..].iter().rev().zip(right[prefix..].iter().rev())
+        .take_while(|(lline, rline)| lline == rline)
+        .count();
+    for idx in 0..prefix {
+        leftkept[idx] = true;
+        rightkept[idx] = true;
+    }
+    for idx in 1..=suffix {
+        leftkept[left.len() - idx] = true;
+        rightkept[right.len() - idx] = true;
+    }
     // endregion
 
-    // region Create the filter string & set the filter.
-    let useext;
-    if !sggstdextnsn.starts_with("*.") {
-        useext = format!("*.{}", sggstdextnsn);
-    } else {
-        useext = sggstdextnsn.to_string();
+    let (lend, rend) = (left.len() - suffix, right.len() - suffix);
+    if prefix == lend || prefix == rend {
+        return;  // One side is used up, so the rest of the other side is all removed or all added.
+    }
+
+    let (lsplit, rsplit) = match file_diff_middle_snake(&left[prefix..lend], &right[prefix..rend]) {
+        Some(split) => split,
+        None => return,  // Nothing in common.
+    };
+    let (lsplit, rsplit) = (prefix + lsplit, prefix + rsplit);
+    file_diff_mark(&left[prefix..lsplit], &right[prefix..rsplit],
+                   &mut leftkept[prefix..lsplit], &mut rightkept[prefix..rsplit]);
+    file_diff_mark(&left[lsplit..lend], &right[rsplit..rend],
+                   &mut leftkept[lsplit..lend], &mut rightkept[rsplit..rend]);
+}
+
+/// Finds where a shortest edit from `left` to `right` can be split in two,
+/// by running Myers' search forward from the start and backward from the
+/// end until the two meet.  Expects both sides to be non-empty and to
+/// differ in their first and in their last lines, so the split always
+/// leaves two smaller problems.
+fn file_diff_middle_snake(left: &[String], right: &[String]) -> Option<(usize, usize)> {
+    let (lcount, rcount) = (left.len() as isize, right.len() as isize);
+    let maxd = (lcount + rcount + 1) / 2;
+    let offset = maxd;
+    let vlen = 2 * maxd + 2;
+    let mut forward = vec![-1isize; vlen as usize];
+    let mut backward = vec![-1isize; vlen as usize];
+    forward[(offset + 1) as usize] = 0;
+    backward[(offset + 1) as usize] = 0;
+
+    let delta = lcount - rcount;
+    let meet_forward = delta % 2 != 0;  // Which search is the one to notice the overlap.
+    let (mut fstart, mut fend, mut bstart, mut bend) = (0, 0, 0, 0);
+
+    for depth in 0..maxd {
+
+        // region One more step forward from the start.
+        let mut diag = -depth + fstart;
+        while diag <= depth - fend {
+            let at = (offset + diag) as usize;
+            let mut lidx = match diag == -depth || (diag != depth && forward[at - 1] < forward[at + 1]) {
+                true => forward[at + 1],
+                false => forward[at - 1] + 1,
+            };
+            let mut ridx = lidx - diag;
+            while lidx < lcount && ridx < rcount && left[lidx as usize] == right[ridx as usize] {
+                lidx += 1;
+                ridx += 1;
+            }
+            forward[at] = lidx;
+
+            if lidx > lcount {
+                fend += 2;  // Ran off the right edge.
+            } else if ridx > rcount {
+                fstart += 2;  // Ran off the bottom edge.
+            } else if meet_forward {
+                let other = offset + delta - diag;
+                if other >= 0 && other < vlen && backward[other as usize] != -1
+                        && lidx >= lcount - backward[other as usize] {
+                    return Some((lidx as usize, ridx as usize));
+                }
+            }
+            diag += 2;
+        }
+        // endregion
+
+        // region One more step backward from the end.
+        let mut diag = -depth + bstart;
+        while diag <= depth - bend {
+            let at = (offset + diag) as usize;
+            let mut lback = match diag == -depth || (diag != depth && backward[at - 1] < backward[at + 1]) {
+                true => backward[at + 1],
+                false => backward[at - 1] + 1,
+            };
+            let mut rback = lback - diag;
+            while lback < lcount && rback < rcount
+                    && left[(lcount - lback - 1) as usize] == right[(rcount - rback - 1) as usize] {
+                lback += 1;
+                rback += 1;
+            }
+            backward[at] = lback;
+
+            if lback > lcount {
+                bend += 2;
+            } else if rback > rcount {
+                bstart += 2;
+            } else if !meet_forward {
+                let other = offset + delta - diag;
+                if other >= 0 && other < vlen && forward[other as usize] != -1 {
+                    let lidx = forward[other as usize];
+                    let ridx = lidx - (other - offset);
+                    if lidx >= lcount - lback {
+                        return Some((lidx as usize, ridx as usize));
+                    }
+                }
+            }
+            diag += 2;
+        }
+        // endregion
+    }
+    None
+}
+
+/// Turns a run of removed and added lines into rows:  pairs first, as
+/// `Changed`, then the leftovers.
+fn file_diff_flush(rows: &mut Vec<DiffRow>, removed: &mut Vec<String>, added: &mut Vec<String>) {
+    let mut removed_iter = removed.drain(..);
+    let mut added_iter = added.drain(..);
+    loop {
+        let row = match (removed_iter.next(), added_iter.next()) {
+            (Some(left), Some(right)) => DiffRow { kind: DiffKind::Changed, left: Some(left), right: Some(right) },
+            (Some(left), None) => DiffRow { kind: DiffKind::Removed, left: Some(left), right: None },
+            (None, Some(right)) => DiffRow { kind: DiffKind::Added, left: None, right: Some(right) },
+            (None, None) => break,
+        };
+        rows.push(row);
+    }
+}
+
+/// Opens a window showing the files at `leftpath` and `rightpath` side by
+/// side, with added, removed and changed lines highlighted.  Scrolling
+/// either side scrolls the other to the same row.
+///
+/// The window is shown and returned; it stays open until the user closes
+/// it, so keep the app running (`app.run()`) after calling this.
+///
+/// # Example
+///
+/// ```ignore
+/// fn main() {
+///     let app = app::App::default();
+///     match fltk_compare_files("/home/user/banks/algebra_v1.bnk", "/home/user/banks/algebra_v2.bnk") {
+///         Ok(_win) => app.run().unwrap(),
+///         Err(err) => println!("Can't compare them: {}", err),
+///     }
+/// }
+/// ```
+///
+pub fn fltk_compare_files(leftpath: &str, rightpath: &str) -> Result<Window, LibFileError> {
+    let leftlines: Vec<String> = file_read_to_string(leftpath)?.lines().map(|line| line.to_string()).collect();
+    let rightlines: Vec<String> = file_read_to_string(rightpath)?.lines().map(|line| line.to_string()).collect();
+    let rows = file_diff_lines(&leftlines, &rightlines);
+
+    // region Lay out the window:  a path above each pane.
+    let (winw, winh, headh) = (1000, 700, 25);
+    let mut win = Window::new(100, 100, winw, winh, "Compare Files");
+    let mut lefthead = Frame::new(0, 0, winw / 2, headh, None);
+    lefthead.set_label(&fltk_escape_label(leftpath));
+    let mut righthead = Frame::new(winw / 2, 0, winw / 2, headh, None);
+    righthead.set_label(&fltk_escape_label(rightpath));
+    let mut leftpane = TextDisplay::new(0, headh, winw / 2, winh - headh, None);
+    let mut rightpane = TextDisplay::new(winw / 2, headh, winw / 2, winh - headh, None);
+    win.end();
+    win.resizable(&win);
+    // endregion
+
+    // region Fill each pane with its side of the rows, and its highlighting.
+    let styles = fltk_compare_styles();
+    for (pane, leftside) in [(&mut leftpane, true), (&mut rightpane, false)] {
+        let (text, stylestr) = fltk_compare_text(&rows, leftside);
+        pane.set_buffer(TextBuffer::default());
+        match pane.buffer() {
+            Some(mut buffer) => buffer.set_text(&text),
+            None => return Err(LibFileError::Dialog("The comparison pane has no text buffer.".to_string())),
+        }
+        let mut stylebuffer = TextBuffer::default();
+        stylebuffer.set_text(&stylestr);
+        pane.set_text_font(Font::Courier);
+        pane.set_highlight_data_ext(stylebuffer, styles.clone());
     }
-    let combined_filter = format!("List Files\t{}\nAll Files\t*.*", useext);
-    fchooser.set_filter(&combined_filter);
     // endregion
 
-    // Set the title of the dialog browser.
-    fchooser.set_title(wintitle);
+    fltk_sync_scrolling(&mut leftpane, &rightpane);
+    fltk_sync_scrolling(&mut rightpane, &leftpane);
+
+    win.show();
+    Ok(win)
+}
+
+/// Lets the user choose two files, one after the other, starting in
+/// `startdir`, then compares them with `fltk_compare_files`.  Returns
+/// `Ok(None)` if the user cancels either choice.
+///
+/// # Example
+///
+/// ```ignore
+/// fn main() {
+///     let app = app::App::default();
+///     match fltk_compare_pick("/home/user/banks") {
+///         Ok(Some(_win)) => app.run().unwrap(),
+///         Ok(None) => println!("The user cancelled."),
+///         Err(err) => println!("Can't compare them: {}", err),
+///     }
+/// }
+/// ```
+///
+pub fn fltk_compare_pick(startdir: &str) -> Result<Option<Window>, LibFileError> {
+    let leftpath = match DialogBuilder::new(DialogMode::Open)
+        .start_dir(startdir)
+        .title("Choose the older file")
+        .show()? {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    let rightpath = match DialogBuilder::new(DialogMode::Open)
+        .start_dir(&leftpath)
+        .title("Choose the newer file")
+        .show()? {
+        Some(path) => path,
+        None => return Ok(None),
+    };
 
-    fchooser.show();
+    Ok(Some(fltk_compare_files(&leftpath, &rightpath)?))
+}
 
-    let path = fchooser.filename().to_str().unwrap().to_string();
+/// The style table for the panes:  plain, added, removed, changed, and filler.
+fn fltk_compare_styles() -> Vec<StyleTableEntryExt> {
+    let entry = |bgcolor: Color, attr: TextAttr| StyleTableEntryExt {
+        color: Color::Black,
+        font: Font::Courier,
+        size: app::font_size(),
+        attr,
+        bgcolor,
+    };
+    vec![
+        entry(Color::White, TextAttr::None),                        // A:  same
+        entry(Color::from_rgb(200, 240, 200), TextAttr::BgColorExt), // B:  added
+        entry(Color::from_rgb(245, 200, 200), TextAttr::BgColorExt), // C:  removed
+        entry(Color::from_rgb(250, 240, 180), TextAttr::BgColorExt), // D:  changed
+        entry(Color::from_rgb(225, 225, 225), TextAttr::BgColorExt), // E:  no line on this side
+    ]
+}
 
-    path
-}
\ No newline at end of file
+/// Builds one pane's text and the matching style string (one style letter
+/// per byte of text).
+fn fltk_compare_text(rows: &[DiffRow], leftside: bool) -> (String, String) {
+    let mut text = String::new();
+    let mut stylestr = String::new();
+
+    for row in rows {
+        let line = match leftside {
+            true => row.left.as_deref(),
+            false => row.right.as_deref(),
+        };
+        let style = match (line, row.kind) {
+            (None, _) => 'E',
+            (Some(_), DiffKind::Same) => 'A',
+            (Some(_), DiffKind::Added) => 'B',
+            (Some(_), DiffKind::Removed) => 'C',
+            (Some(_), DiffKind::Changed) => 'D',
+        };
+        let line = line.unwrap_or("");
+        text.push_str(line);
+        text.push('\n');
+        stylestr.extend(std::iter::repeat_n(style, line.len() + 1));
+    }
+    (text, stylestr)
+}
+
+/// Makes `target` follow `source` whenever something happens in `source`
+/// that could scroll it.  The follow-up waits until `source` has handled
+/// the event and moved.
+fn fltk_sync_scrolling(source: &mut TextDisplay, target: &TextDisplay) {
+    let usesource = source.clone();
+    let usetarget = target.clone();
+    source.handle(move |_, event| {
+        if matches!(event, Event::MouseWheel | Event::Push | Event::Drag | Event::Released
+                           | Event::KeyDown | Event::Resize) {
+            let (from, mut to) = (usesource.clone(), usetarget.clone());
+            app::add_timeout3(0.0, move |_| {
+                let (topline, hoffset) = match fltk_scroll_position(&from) {
+                    Some(position) => position,
+                    None => return,
+                };
+                to.scroll(topline, hoffset);
+            });
+        }
+        false
+    });
+}
+
+/// The top line and horizontal offset of a pane, read from its own
+/// scrollbars.  `TextDisplay` keeps its vertical scrollbar as its first
+/// child and its horizontal one as its second.
+fn fltk_scroll_position(pane: &TextDisplay) -> Option<(i32, i32)> {
+    let group = pane.as_group()?;
+    let vbar = Scrollbar::from_dyn_widget(&group.child(0)?)?;
+    let hbar = Scrollbar::from_dyn_widget(&group.child(1)?)?;
+    Some((vbar.value() as i32, hbar.value() as i32))
+}
+
+/// Escapes `@` and `&` so a path shows up as-is in a widget label.
+fn fltk_escape_label(text: &str) -> String {
+    text.replace('@', "@@").replace('&', "&&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.split_whitespace().map(|line| line.to_string()).collect()
+    }
+
+    fn kinds(rows: &[DiffRow]) -> Vec<DiffKind> {
+        rows.iter().map(|row| row.kind).collect()
+    }
+
+    /// The length of a longest common subsequence, the slow and sure way.
+    fn lcs_len(left: &[String], right: &[String]) -> usize {
+        let mut lengths = vec![vec![0; right.len() + 1]; left.len() + 1];
+        for lidx in (0..left.len()).rev() {
+            for ridx in (0..right.len()).rev() {
+                lengths[lidx][ridx] = match left[lidx] == right[ridx] {
+                    true => lengths[lidx + 1][ridx + 1] + 1,
+                    false => lengths[lidx + 1][ridx].max(lengths[lidx][ridx + 1]),
+                };
+            }
+        }
+        lengths[0][0]
+    }
+
+    #[test]
+    fn identical_and_empty_files() {
+        assert_eq!(kinds(&file_diff_lines(&lines("a b c"), &lines("a b c"))), [DiffKind::Same; 3]);
+        assert!(file_diff_lines(&[], &[]).is_empty());
+        assert_eq!(kinds(&file_diff_lines(&[], &lines("a b"))), [DiffKind::Added; 2]);
+        assert_eq!(kinds(&file_diff_lines(&lines("a b"), &[])), [DiffKind::Removed; 2]);
+    }
+
+    #[test]
+    fn rewritten_lines_pair_up_as_changed() {
+        let rows = file_diff_lines(&lines("a b c"), &lines("a B c d"));
+        assert_eq!(kinds(&rows), [DiffKind::Same, DiffKind::Changed, DiffKind::Same, DiffKind::Added]);
+        assert_eq!(rows[1].left.as_deref(), Some("b"));
+        assert_eq!(rows[1].right.as_deref(), Some("B"));
+        assert_eq!(rows[3].left, None);
+    }
+
+    #[test]
+    fn leftover_lines_are_removed_or_added() {
+        let rows = file_diff_lines(&lines("a x y z b"), &lines("a X b"));
+        assert_eq!(kinds(&rows), [DiffKind::Same, DiffKind::Changed, DiffKind::Removed, DiffKind::Removed,
+                                  DiffKind::Same]);
+        assert_eq!(rows[1].left.as_deref(), Some("x"));
+        assert_eq!(rows[1].right.as_deref(), Some("X"));
+        assert_eq!(rows[3].right, None);
+
+        let rows = file_diff_lines(&lines("a b"), &lines("a n1 n2 b"));
+        assert_eq!(kinds(&rows), [DiffKind::Same, DiffKind::Added, DiffKind::Added, DiffKind::Same]);
+    }
+
+    #[test]
+    fn shared_lines_are_a_longest_common_subsequence() {
+        let mut seed: u32 = 12345;
+        let mut next = move || {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 16) % 4
+        };
+        for _ in 0..300 {
+            let leftlen = next() as usize * 3;
+            let rightlen = next() as usize * 3;
+            let left: Vec<String> = (0..leftlen).map(|_| next().to_string()).collect();
+            let right: Vec<String> = (0..rightlen).map(|_| next().to_string()).collect();
+
+            let rows = file_diff_lines(&left, &right);
+            let same = rows.iter().filter(|row| row.kind == DiffKind::Same).count();
+            assert_eq!(same, lcs_len(&left, &right), "{:?} vs {:?}", left, right);
+
+            // Each side's lines come back complete and in order.
+            let leftback: Vec<String> = rows.iter().filter_map(|row| row.left.clone()).collect();
+            let rightback: Vec<String> = rows.iter().filter_map(|row| row.right.clone()).collect();
+            assert_eq!(leftback, left);
+            assert_eq!(rightback, right);
+        }
+    }
+
+    #[test]
+    fn long_files_with_few_changes() {
+        let older: Vec<String> = (0..30_000).map(|idx| format!("line {}", idx)).collect();
+        let mut newer = older.clone();
+        newer[10] = "changed".to_string();
+        newer.remove(20_000);
+        newer.insert(25_000, "added".to_string());
+
+        let rows = file_diff_lines(&older, &newer);
+        let count = |kind: DiffKind| rows.iter().filter(|row| row.kind == kind).count();
+        assert_eq!(count(DiffKind::Changed), 1);
+        assert_eq!(count(DiffKind::Removed), 1);
+        assert_eq!(count(DiffKind::Added), 1);
+        assert_eq!(count(DiffKind::Same), 29_998);
+    }
+}
@@ -1,19 +1,338 @@
-use std::path::Path;
+//! An open-file window with a preview pane, so the user can check they
+//! have the right file before opening it.  Text files show their first
+//! lines and the encoding they seem to be in; anything else shows its
+//! size, modification time and type.
 
-pub fn file_fullpath(mut usedir: &String) -> String {
+use fltk::{app, button::Button, enums::Font, group::Tile, prelude::*, window::Window};
+use fltk::text::{TextBuffer, TextDisplay};
+use std::{cell::RefCell, fs, io::Read, path::Path, rc::Rc, time::{SystemTime, UNIX_EPOCH}};
+use crate::dir_mngmnt::dir_resolve_or_home;
+use crate::file_errors::LibFileError;
+use crate::file_fltk::FilterSet;
+use crate::file_widgets::{DirTree, FileListPanel};
 
-    // Make sure the passed directory exists and `startpath` is ready.
-    let track = dir_check_valid(&mut usedir);
-    let startpath = Path::new(track.as_str());
+/// How many bytes are read from a file to preview it.
+const PREVIEW_BYTES: usize = 64 * 1024;
 
-    // Set the dialog browser to the correct directory.
-    let mut dialog = dialog::NativeFileChooser::new(dialog
-                                                ::NativeFileChooserType
-                                                ::BrowseFile);
-    dialog.set_directory(&startpath).expect("Directory does not exist.");
+/// The text encodings a preview can recognise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// Plain 7-bit ASCII.
+    Ascii,
+    /// UTF-8 with no byte-order mark.
+    Utf8,
+    /// UTF-8 starting with a byte-order mark.
+    Utf8Bom,
+    /// UTF-16, little-endian, with a byte-order mark.
+    Utf16Le,
+    /// UTF-16, big-endian, with a byte-order mark.
+    Utf16Be,
+    /// Not valid UTF-8, but with no control bytes either, so most likely
+    /// Latin-1 (or Windows-1252).  This one is a guess.
+    Latin1,
+}
 
-    dialog.show();
+impl TextEncoding {
+    /// The name shown in the preview pane.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextEncoding::Ascii => "ASCII",
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf8Bom => "UTF-8 (with BOM)",
+            TextEncoding::Utf16Le => "UTF-16 LE",
+            TextEncoding::Utf16Be => "UTF-16 BE",
+            TextEncoding::Latin1 => "Latin-1 (probably)",
+        }
+    }
+}
 
-    let path = dialog.filename().to_str().unwrap().to_string();
-    path
-}
\ No newline at end of file
+/// What the preview pane shows for one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilePreview {
+    /// A text file:  its encoding, its first lines, and whether there is
+    /// more to it than those lines.
+    Text { encoding: TextEncoding, lines: Vec<String>, truncated: bool },
+    /// Anything else:  its size in bytes, when it was last modified (if the
+    /// system says), and a description of its type such as `"PNG image"`.
+    Other { size: u64, modified: Option<SystemTime>, kind: String },
+}
+
+/// Works out what to show in a preview of the file at `path`.
+///
+/// Only the start of the file is read, so large files are cheap to preview.
+/// A file that decodes as text gives `FilePreview::Text` with at most
+/// `maxlines` lines; anything else gives `FilePreview::Other`.
+///
+/// # Example
+///
+/// ```ignore
+/// fn main() {
+///     match file_preview("/home/user/lists/algebra.lst", 20) {
+///         Ok(preview) => println!("{}", file_preview_text(&preview)),
+///         Err(err) => println!("{}", err),
+///     }
+/// }
+/// ```
+///
+pub fn file_preview(path: &str, maxlines: usize) -> Result<FilePreview, LibFileError> {
+    let metadata = fs::metadata(path).map_err(|err| LibFileError::from_io(err, path))?;
+    if !metadata.is_file() {
+        return Err(LibFileError::InvalidPath(path.to_string()));
+    }
+
+    // region Read the start of the file.
+    let mut bytes = Vec::new();
+    fs::File::open(path)
+        .and_then(|file| file.take(PREVIEW_BYTES as u64).read_to_end(&mut bytes))
+        .map_err(|err| LibFileError::from_io(err, path))?;
+    let whole = (bytes.len() as u64) == metadata.len();
+    // endregion
+
+    match file_decode_text(&bytes, whole) {
+        Some((encoding, text)) => {
+            let mut lines: Vec<String> = text.lines().map(|line| line.to_string()).collect();
+            let truncated = !whole || lines.len() > maxlines;
+            lines.truncate(maxlines);
+            Ok(FilePreview::Text { encoding, lines, truncated })
+        }
+        None => Ok(FilePreview::Other {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            kind: file_guess_kind(path, &bytes),
+        }),
+    }
+}
+
+/// Renders a preview as the text shown in the preview pane.
+pub fn file_preview_text(preview: &FilePreview) -> String {
+    match preview {
+        FilePreview::Text { encoding, lines, truncated } => {
+            let mut text = format!("Encoding:  {}\n\n", encoding.name());
+            for line in lines {
+                text.push_str(line);
+                text.push('\n');
+            }
+            if *truncated {
+                text.push_str("...\n");
+            }
+            text
+        }
+        FilePreview::Other { size, modified, kind } => {
+            let modified = match modified {
+                Some(time) => file_format_time(*time),
+                None => "unknown".to_string(),
+            };
+            format!("Type:  {}\nSize:  {}\nModified:  {}\n", kind, file_format_size(*size), modified)
+        }
+    }
+}
+
+/// Decodes the start of a file as text, if it looks like text.  A
+/// byte-order mark settles the encoding; without one, see
+/// `file_decode_unmarked`.
+fn file_decode_text(bytes: &[u8], whole: bool) -> Option<(TextEncoding, String)> {
+    let (encoding, text) = match bytes {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => (TextEncoding::Utf8Bom, String::from_utf8_lossy(rest).into_owned()),
+        [0xFF, 0xFE, rest @ ..] => (TextEncoding::Utf16Le, file_decode_utf16(rest, u16::from_le_bytes)),
+        [0xFE, 0xFF, rest @ ..] => (TextEncoding::Utf16Be, file_decode_utf16(rest, u16::from_be_bytes)),
+        _ => return file_decode_unmarked(bytes, whole),
+    };
+    Some((encoding, text))
+}
+
+/// Decodes UTF-16 bytes (after the byte-order mark), with `to_unit` putting
+/// each pair of bytes together in the right order.
+fn file_decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| to_unit([pair[0], pair[1]])).collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Decodes text that has no byte-order mark.  Control bytes mean binary;
+/// otherwise it is ASCII, then UTF-8, then Latin-1 as a last guess.  `whole`
+/// says whether `bytes` is the whole file; if not, a multi-byte character
+/// cut off at the end is not held against it.
+fn file_decode_unmarked(bytes: &[u8], whole: bool) -> Option<(TextEncoding, String)> {
+    let is_control = |byte: &u8| *byte < 0x20 && !matches!(byte, b'\n' | b'\r' | b'\t' | 0x0C);
+    if bytes.iter().any(is_control) {
+        return None;
+    }
+    if bytes.is_ascii() {
+        return Some((TextEncoding::Ascii, String::from_utf8_lossy(bytes).into_owned()));
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Some((TextEncoding::Utf8, text.to_string())),
+        Err(err) if !whole && err.error_len().is_none() => {
+            Some((TextEncoding::Utf8, String::from_utf8_lossy(&bytes[..err.valid_up_to()]).into_owned()))
+        }
+        Err(_) => Some((TextEncoding::Latin1, bytes.iter().map(|byte| *byte as char).collect())),
+    }
+}
+
+/// Describes a non-text file from its first bytes, or its extension when
+/// the bytes don't say.
+fn file_guess_kind(path: &str, bytes: &[u8]) -> String {
+    let magic: [(&[u8], &str); 9] = [
+        (b"\x89PNG", "PNG image"),
+        (b"\xFF\xD8\xFF", "JPEG image"),
+        (b"GIF8", "GIF image"),
+        (b"%PDF", "PDF document"),
+        (b"PK\x03\x04", "ZIP archive (or an office document)"),
+        (b"\x1F\x8B", "gzip archive"),
+        (b"\x7FELF", "ELF executable"),
+        (b"MZ", "Windows executable"),
+        (b"SQLite format 3", "SQLite database"),
+    ];
+    match magic.iter().find(|(prefix, _)| bytes.starts_with(prefix)) {
+        Some((_, kind)) => kind.to_string(),
+        None => match Path::new(path).extension() {
+            Some(extnsn) => format!("binary data (.{})", extnsn.to_string_lossy()),
+            None => "binary data".to_string(),
+        },
+    }
+}
+
+/// Formats a size in bytes for people:  `512 bytes`, `1.5 KB`, `3.2 MB`.
+fn file_format_size(size: u64) -> String {
+    let units = ["KB", "MB", "GB", "TB"];
+    if size < 1024 {
+        return format!("{} bytes", size);
+    }
+    let mut scaled = size as f64 / 1024.0;
+    let mut unit = 0;
+    while scaled >= 1024.0 && unit < units.len() - 1 {
+        scaled /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", scaled, units[unit])
+}
+
+/// Formats a time as `YYYY-MM-DD HH:MM UTC`.
+fn file_format_time(time: SystemTime) -> String {
+    let secs = match time.duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_secs() as i64,
+        Err(_) => return "before 1970".to_string(),
+    };
+
+    // region Turn days since 1970 into a calendar date.
+    let (days, daysecs) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+    let shifted = days + 719_468;  // Count from 0000-03-01 so leap days fall at the end of a year.
+    let era = shifted.div_euclid(146_097);
+    let dayofera = shifted.rem_euclid(146_097);
+    let yearofera = (dayofera - dayofera / 1460 + dayofera / 36_524 - dayofera / 146_096) / 365;
+    let dayofyear = dayofera - (365 * yearofera + yearofera / 4 - yearofera / 100);
+    let monthindex = (5 * dayofyear + 2) / 153;
+    let day = dayofyear - (153 * monthindex + 2) / 5 + 1;
+    let month = if monthindex < 10 { monthindex + 3 } else { monthindex - 9 };
+    let year = yearofera + era * 400 + if month <= 2 { 1 } else { 0 };
+    // endregion
+
+    format!("{:04}-{:02}-{:02} {:02}:{:02} UTC", year, month, day, daysecs / 3600, (daysecs % 3600) / 60)
+}
+
+/// Opens a window for choosing a file to open, with a preview of the
+/// highlighted file beside the list.
+///
+/// The window has a folder tree rooted at the top of the file system, the
+/// files in the chosen folder that match `filters` (all files if the set is
+/// empty), and a pane showing a preview of up to `maxlines` lines.  The list
+/// starts in `sggstdpath`, resolved the same way as in `file_fullpath`, and
+/// the tree starts opened down to it.
+///
+/// Returns the same thing `file_fullpath` does:  `Ok(Some(path))` once the
+/// user opens a file (with the Open button or a double-click), and
+/// `Ok(None)` if they cancel or close the window.  Returns `Err` if the
+/// start directory can't be read.
+///
+/// # Example
+///
+/// ```ignore
+/// fn main() {
+///     let app = app::App::default();
+///     let filters = FilterSet::new(&[("Lists", "*.lst")])
+///         .expect("The filters are written correctly.");
+///
+///     match fltk_open_with_preview("/home/user/lists", &filters, 30) {
+///         Ok(Some(path)) => println!("Opening {}", path),
+///         Ok(None) => println!("The user cancelled."),
+///         Err(err) => println!("{}", err),
+///     }
+/// }
+/// ```
+///
+pub fn fltk_open_with_preview(sggstdpath: &str, filters: &FilterSet, maxlines: usize)
+                                                        -> Result<Option<String>, LibFileError> {
+    let startdir = dir_resolve_or_home(sggstdpath);
+    fs::read_dir(&startdir).map_err(|err| LibFileError::from_io(err, &startdir))?;
+    let fsroot = match Path::new(&startdir).ancestors().last() {
+        Some(fsroot) => fsroot.to_string_lossy().into_owned(),  // `/`, or the drive on Windows.
+        None => startdir.clone(),
+    };
+    let chosen: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+
+    // region Lay out the window:  folders and files on the left, preview on the right, buttons below.
+    let (winw, winh, buttonh) = (900, 600, 40);
+    let mut win = Window::new(100, 100, winw, winh, "Open File");
+    let tile = Tile::new(0, 0, winw, winh - buttonh, None);
+    let mut dirtree = DirTree::new(0, 0, 300, (winh - buttonh) / 2, &fsroot);
+    let mut panel = FileListPanel::new(0, (winh - buttonh) / 2, 300, (winh - buttonh) / 2, &startdir, filters);
+    let mut previewpane = TextDisplay::new(300, 0, winw - 300, winh - buttonh, None);
+    tile.end();
+    let mut openbutton = Button::new(winw - 220, winh - buttonh + 5, 100, 30, "Open");
+    let mut cancelbutton = Button::new(winw - 110, winh - buttonh + 5, 100, 30, "Cancel");
+    win.end();
+    win.resizable(&tile);
+    win.make_modal(true);
+
+    let previewbuffer = TextBuffer::default();
+    previewpane.set_buffer(previewbuffer.clone());
+    previewpane.set_text_font(Font::Courier);
+    openbutton.deactivate();
+    let _ = dirtree.open_dir(&startdir);  // A hidden start folder just leaves the tree closed.
+    // endregion
+
+    // region Wire the pieces together.
+    let (mut usepanel, mut usebuffer, mut usebutton) = (panel.clone(), previewbuffer.clone(), openbutton.clone());
+    dirtree.on_select(move |dir| {
+        let _ = usepanel.set_dir(dir);  // An unreadable folder just shows an empty list.
+        usebuffer.set_text("");  // Nothing is selected in the new folder yet.
+        usebutton.deactivate();
+    });
+
+    let (mut usebuffer, mut usebutton) = (previewbuffer.clone(), openbutton.clone());
+    panel.on_select(move |path| {
+        let text = match file_preview(path, maxlines) {
+            Ok(preview) => file_preview_text(&preview),
+            Err(err) => format!("Can't preview this file:\n{}", err),
+        };
+        usebuffer.set_text(&text);
+        usebutton.activate();
+    });
+
+    let (usechosen, mut usewin) = (chosen.clone(), win.clone());
+    panel.on_activate(move |path| {
+        *usechosen.borrow_mut() = Some(path.to_string());
+        usewin.hide();
+    });
+
+    let (usepanel, usechosen, mut usewin) = (panel.clone(), chosen.clone(), win.clone());
+    openbutton.set_callback(move |_| {
+        let selected = match usepanel.selected() {
+            Some(selected) => selected,
+            None => return,  // Nothing to open, so don't take the click as a cancel.
+        };
+        *usechosen.borrow_mut() = Some(selected);
+        usewin.hide();
+    });
+
+    let mut usewin = win.clone();
+    cancelbutton.set_callback(move |_| usewin.hide());
+    // endregion
+
+    win.show();
+    while win.shown() {
+        app::wait();
+    }
+
+    let result = chosen.borrow_mut().take();
+    Ok(result)
+}